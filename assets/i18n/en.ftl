@@ -0,0 +1,85 @@
+# 英文文本，键名和 zh.ftl 保持一致
+window-title = Snake
+play = Play
+restart = Restart
+game-over = Game Over
+score-label = Score:{" "}
+streak-label = {"  "}Streak:{" "}
+best-streak = Best streak: { $streak }
+cause-Wall = You hit the wall
+cause-Body = You ran into yourself
+cause-Obstacle = You hit an obstacle
+cause-Enemy = An enemy got you
+cause-DangerZone = You were caught in the danger zone
+cause-Timeout = Time's up
+breakdown-line = { $food } x{ $count }  { $points }
+main-menu = Main Menu
+high-scores = High Scores
+no-high-scores = No scores yet
+high-score-line = { $rank }.  { $score }  (streak { $streak })
+credits = Credits
+credits-text =
+    Made with Bevy
+    Chinese font: Noto Sans SC (SIL Open Font License)
+    Colour-blind palettes based on Okabe & Ito
+continue = Continue
+save-broken = The saved game could not be loaded and was moved to save.broken.ron
+save-incompatible = This save is from version { $found } and cannot be loaded by version { $expected }; it was moved to save.broken.ron
+quit = Quit
+choose-mode = Choose a Mode
+mode-Classic = Classic
+mode-TimeAttack = Time Attack ({ $secs }s)
+mode-Survival = Survival
+hud-time-left = Time left: { $secs }s
+hud-survived = Survived: { $secs }s
+settings = Settings
+back = Back
+resume = Resume
+paused = Paused
+press-key = Press a key...
+
+setting-row = { $name }: { $value }
+setting-arena = Arena
+setting-speed = Speed
+setting-walls = Walls
+setting-collision = Tail
+setting-portals = Portals
+setting-enemies = Enemies
+setting-theme = Theme
+setting-volume = Volume
+setting-language = Language
+setting-effects = Effects
+setting-movement = Smooth movement
+setting-fps = Show FPS
+value-on = On
+value-off = Off
+speed-Slow = Slow
+speed-Normal = Normal
+speed-Fast = Fast
+walls-Solid = Solid
+walls-Wrap = Wrap
+collision-TailVacates = Moves out of the way
+collision-Strict = Blocks the head
+
+control-Up = Up
+control-Down = Down
+control-Left = Left
+control-Right = Right
+
+food-Normal = Food
+food-Golden = Golden
+food-Rare = Rare
+food-Poison = Poison
+score-Survival = Survival
+
+powerup-Slow = Slow
+powerup-Ghost = Ghost
+powerup-Magnet = Magnet
+powerup-Shrink = Shrink
+powerup-Double = Double
+
+# 终端版本
+tui-mode = Mode: { $mode }
+tui-hint-menu = Enter: Play   m: Mode   q: Quit
+tui-hint-playing = Arrows/WASD: Move   q: Quit
+tui-hint-game-over = Enter: Restart   m: Mode   q: Quit
@@ -0,0 +1,85 @@
+# 中文文本，键名和 en.ftl 保持一致
+window-title = 贪吃蛇
+play = 开始
+restart = 再来一局
+game-over = 游戏结束
+score-label = 分数：
+streak-label = {"  "}连击：
+best-streak = 最长连击：{ $streak }
+cause-Wall = 撞到了墙
+cause-Body = 撞到了自己
+cause-Obstacle = 撞到了障碍物
+cause-Enemy = 被敌人抓住了
+cause-DangerZone = 困在了危险区
+cause-Timeout = 时间到
+breakdown-line = { $food } x{ $count }  { $points }
+main-menu = 主菜单
+high-scores = 排行榜
+no-high-scores = 还没有成绩
+high-score-line = { $rank }.  { $score }（连击 { $streak }）
+credits = 致谢
+credits-text =
+    使用 Bevy 制作
+    中文字体：思源黑体 Noto Sans SC（SIL 开源字体许可）
+    色弱配色参考 Okabe 和 Ito 的配色方案
+continue = 继续游戏
+save-broken = 存档无法读取，已改名为 save.broken.ron
+save-incompatible = 存档版本为 { $found }，当前版本 { $expected } 无法读取，已改名为 save.broken.ron
+quit = 退出
+choose-mode = 选择模式
+mode-Classic = 经典
+mode-TimeAttack = 限时（{ $secs } 秒）
+mode-Survival = 生存
+hud-time-left = 剩余时间：{ $secs } 秒
+hud-survived = 已存活：{ $secs } 秒
+settings = 设置
+back = 返回
+resume = 继续
+paused = 已暂停
+press-key = 请按下一个键……
+
+setting-row = { $name }：{ $value }
+setting-arena = 场地
+setting-speed = 速度
+setting-walls = 墙壁
+setting-collision = 尾巴
+setting-portals = 传送门
+setting-enemies = 敌人
+setting-theme = 主题
+setting-volume = 音量
+setting-language = 语言
+setting-effects = 特效
+setting-movement = 平滑移动
+setting-fps = 显示帧率
+value-on = 开
+value-off = 关
+speed-Slow = 慢
+speed-Normal = 中
+speed-Fast = 快
+walls-Solid = 撞墙结束
+walls-Wrap = 穿墙
+collision-TailVacates = 先让开
+collision-Strict = 挡住蛇头
+
+control-Up = 上
+control-Down = 下
+control-Left = 左
+control-Right = 右
+
+food-Normal = 食物
+food-Golden = 金色食物
+food-Rare = 稀有食物
+food-Poison = 毒食物
+score-Survival = 存活
+
+powerup-Slow = 减速
+powerup-Ghost = 幽灵
+powerup-Magnet = 磁铁
+powerup-Shrink = 缩短
+powerup-Double = 双倍
+
+# 终端版本
+tui-mode = 模式：{ $mode }
+tui-hint-menu = Enter：开始   m：切换模式   q：退出
+tui-hint-playing = 方向键/WASD：移动   q：退出
+tui-hint-game-over = Enter：重新开始   m：切换模式   q：退出
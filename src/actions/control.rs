@@ -1,8 +1,10 @@
-use bevy::prelude::{ButtonInput, KeyCode, Res};
+use bevy::prelude::{ButtonInput, KeyCode, Res, Resource};
+use serde::{Deserialize, Serialize};
 
+use super::Position;
 use crate::snake::SnakeHead;
 
-#[derive(Default, Clone, Copy, Eq, PartialEq, Debug, Hash)]
+#[derive(Default, Clone, Copy, Eq, PartialEq, Debug, Hash, Serialize, Deserialize)]
 pub enum GameControl {
     #[default]
     Up,
@@ -11,23 +13,96 @@ pub enum GameControl {
     Right,
 }
 
+/// 每个方向对应的按键，可以在设置里修改
+#[derive(Resource, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct KeyBindings {
+    pub up: Vec<KeyCode>,
+    pub down: Vec<KeyCode>,
+    pub left: Vec<KeyCode>,
+    pub right: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: vec![KeyCode::ArrowUp, KeyCode::KeyW],
+            down: vec![KeyCode::ArrowDown, KeyCode::KeyS],
+            left: vec![KeyCode::ArrowLeft, KeyCode::KeyA],
+            right: vec![KeyCode::ArrowRight, KeyCode::KeyD],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, control: GameControl) -> &[KeyCode] {
+        match control {
+            GameControl::Up => &self.up,
+            GameControl::Down => &self.down,
+            GameControl::Left => &self.left,
+            GameControl::Right => &self.right,
+        }
+    }
+
+    pub fn set(&mut self, control: GameControl, keys: Vec<KeyCode>) {
+        *match control {
+            GameControl::Up => &mut self.up,
+            GameControl::Down => &mut self.down,
+            GameControl::Left => &mut self.left,
+            GameControl::Right => &mut self.right,
+        } = keys;
+    }
+
+    /// 把 `control` 改为只用 `key` 触发，其他方向上的同一个键会被去掉；
+    /// 某个方向因此没有按键时改用 `control` 原来的按键，相当于两个方向交换按键
+    pub fn bind(&mut self, control: GameControl, key: KeyCode) {
+        let previous = self
+            .keys(control)
+            .iter()
+            .copied()
+            .filter(|&k| k != key)
+            .collect::<Vec<_>>();
+        for other in GameControl::ALL {
+            if other == control || !self.keys(other).contains(&key) {
+                continue;
+            }
+            let mut keys = self
+                .keys(other)
+                .iter()
+                .copied()
+                .filter(|&k| k != key)
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                keys.clone_from(&previous);
+            }
+            self.set(other, keys);
+        }
+        self.set(control, vec![key]);
+    }
+}
+
 impl GameControl {
-    pub fn pressed(keyboard_input: &Res<ButtonInput<KeyCode>>, head : &SnakeHead) -> Self {
-        if keyboard_input.pressed(KeyCode::ArrowLeft) || keyboard_input.pressed(KeyCode::KeyA) {
-            GameControl::Left
-        } else if keyboard_input.pressed(KeyCode::ArrowRight)
-            || keyboard_input.pressed(KeyCode::KeyD)
-        {
-            GameControl::Right
-        } else if keyboard_input.pressed(KeyCode::ArrowDown)
-            || keyboard_input.pressed(KeyCode::KeyS)
-        {
-            GameControl::Down
-        } else if keyboard_input.pressed(KeyCode::ArrowUp)
-        || keyboard_input.pressed(KeyCode::KeyW){
-            GameControl::Up
-        } else {
-            head.direction
+    pub const ALL: [GameControl; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    // 同时按下多个方向时依次取左、右、下、上
+    pub fn pressed(
+        keyboard_input: &Res<ButtonInput<KeyCode>>,
+        bindings: &KeyBindings,
+        head: &SnakeHead,
+    ) -> Self {
+        [Self::Left, Self::Right, Self::Down, Self::Up]
+            .into_iter()
+            .find(|&control| keyboard_input.any_pressed(bindings.keys(control).iter().copied()))
+            .unwrap_or(head.direction)
+    }
+
+    /// 从 `pos` 沿这个方向走一格
+    pub fn step(self, pos: Position) -> Position {
+        let Position { x, y } = pos;
+        match self {
+            Self::Left => Position { x: x - 1, y },
+            Self::Right => Position { x: x + 1, y },
+            Self::Up => Position { x, y: y + 1 },
+            Self::Down => Position { x, y: y - 1 },
         }
     }
 
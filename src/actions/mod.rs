@@ -1,15 +1,9 @@
-use bevy::{
-    app::{App, Plugin, Update},
-    math::Vec3,
-    prelude::{in_state, Component, IntoSystemConfigs, Query, Transform, With},
-    window::Window,
-};
-
-use crate::{GameState, ARENA_HEIGHT, ARENA_WIDTH};
+use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
 pub mod control;
 
-#[derive(Component, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -29,52 +23,3 @@ impl Size {
         }
     }
 }
-
-pub struct ActionsPlugin;
-
-impl Plugin for ActionsPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                size_scaling.run_if(in_state(GameState::Playing)),
-                position_translation.run_if(in_state(GameState::Playing)),
-            ),
-        );
-    }
-}
-
-// 计算方块元素的大小
-pub fn size_scaling(
-    primary_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    mut q: Query<(&Size, &mut Transform)>,
-) {
-    let window = primary_query.get_single().unwrap();
-    for (sprite_size, mut transform) in q.iter_mut() {
-        transform.scale = Vec3::new(
-            sprite_size.width / ARENA_WIDTH as f32 * window.width() as f32,
-            sprite_size.height / ARENA_HEIGHT as f32 * window.height() as f32,
-            1.0,
-        )
-    }
-}
-
-// 计算位移
-pub fn position_translation(
-    primary_query: Query<&Window, With<bevy::window::PrimaryWindow>>,
-    mut q: Query<(&Position, &mut Transform)>,
-) {
-    fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
-        let block_size = bound_window / bound_game;
-        pos / bound_game * bound_window - (bound_window / 2.0) + (block_size / 2.0)
-    }
-
-    let window = primary_query.get_single().unwrap();
-    for (pos, mut transform) in q.iter_mut() {
-        transform.translation = Vec3::new(
-            convert(pos.x as f32, window.width(), ARENA_WIDTH as f32),
-            convert(pos.y as f32, window.height() as f32, ARENA_HEIGHT as f32),
-            0.0,
-        );
-    }
-}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use snake::settings::{DataDir, SettingsFile};
+use snake::tui::{Glyphs, TerminalGuard, TuiPlugin};
+use snake::HeadlessPlugin;
+
+fn main() -> std::io::Result<()> {
+    // 终端不支持方块字符时可以用 --ascii
+    let glyphs = if std::env::args().any(|arg| arg == "--ascii") {
+        Glyphs::ASCII
+    } else {
+        Glyphs::UNICODE
+    };
+
+    // 和窗口版本读写同一份设置和存档，设置要在插件之前插入
+    let file = SettingsFile::user();
+    let settings = file.load();
+
+    let _guard = TerminalGuard::enter()?;
+    App::new()
+        .insert_resource(file)
+        .insert_resource(DataDir::user())
+        .insert_resource(settings)
+        .add_plugins((HeadlessPlugin, TuiPlugin))
+        .insert_resource(glyphs)
+        .run();
+    Ok(())
+}
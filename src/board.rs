@@ -1,5 +1,14 @@
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
 
+use crate::i18n::Localizer;
+use crate::layout::Layout;
+use crate::menu::{ThemedText, UiFont};
+use crate::mode::{GameMode, ModeClock};
+use crate::powerup::{ActiveEffects, PowerUpRegistry};
+use crate::score::{AddScoreEvent, Combo, ComboRules, Score, ScoreSource};
+use crate::settings::Settings;
+use crate::theme::Theme;
 use crate::GameState;
 
 pub struct BoardPlugin;
@@ -7,54 +16,83 @@ pub struct BoardPlugin;
 #[derive(Component)]
 pub struct Board;
 
-#[derive(Default, Resource)]
-pub struct Score(u32);
+/// 右上角正在生效的道具和剩余时间
+#[derive(Component)]
+pub struct EffectsHud;
 
-#[derive(Event)]
-pub struct AddScoreEvent;
+/// 上方中间的模式计时：限时模式显示剩余时间，生存模式显示存活时间
+#[derive(Component)]
+pub struct ModeHud;
 
-impl Plugin for BoardPlugin {
-    fn build(&self, app: &mut App) {
-        app.insert_resource(Score::default())
-            .add_event::<AddScoreEvent>()
-            .add_systems(Startup, setup_board)
-            .add_systems(Update, update_board)
-            .add_systems(OnEnter(GameState::GameOver), reset_score);
-    }
-}
+/// 右下角的帧率，在设置中打开后显示
+#[derive(Component)]
+pub struct FpsText;
 
-impl Score {
-    fn reset(&mut self) {
-        self.0 = 0;
-    }
+/// 吃到食物时在食物位置飘起的分数
+#[derive(Component)]
+pub struct ScorePopup(Timer);
 
-    fn increment(&mut self) {
-        self.0 += 1;
-    }
+/// 分数飘起的速度（像素每秒）
+const POPUP_RISE_SPEED: f32 = 40.0;
 
-    fn get(&self) -> u32 {
-        self.0
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_board)
+            // 分数在 `FixedUpdate` 中结算，`Update` 总在它之后，这一帧的节拍加的分这一帧就显示
+            .add_systems(
+                Update,
+                (
+                    update_board,
+                    update_effects_hud,
+                    update_mode_hud,
+                    update_fps,
+                    spawn_score_popups.run_if(in_state(GameState::Playing)),
+                    animate_score_popups,
+                ),
+            )
+            .add_systems(OnEnter(GameState::GameOver), cleanup_score_popups);
     }
 }
 
-fn setup_board(mut commands: Commands) {
+fn setup_board(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+) {
     commands
         .spawn(
             TextBundle::from_sections([
                 TextSection::new(
-                    "Score: ",
+                    localizer.get("score-label"),
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 20.0,
+                        color: theme.text.0,
+                    },
+                ),
+                TextSection::new(
+                    "0",
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 20.0,
+                        color: theme.text.0,
+                    },
+                ),
+                TextSection::new(
+                    localizer.get("streak-label"),
                     TextStyle {
+                        font: font.0.clone(),
                         font_size: 20.0,
-                        color: Color::WHITE,
-                        ..default()
+                        color: theme.text.0,
                     },
                 ),
                 TextSection::new(
                     "0",
                     TextStyle {
+                        font: font.0.clone(),
                         font_size: 20.0,
-                        color: Color::WHITE,
-                        ..default()
+                        color: theme.text.0,
                     },
                 ),
             ])
@@ -65,26 +103,225 @@ fn setup_board(mut commands: Commands) {
                 ..default()
             }),
         )
-        .insert(Board);
+        .insert((Board, ThemedText::Text));
+
+    commands
+        .spawn(TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        }))
+        .insert(EffectsHud);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 20.0,
+                        color: theme.text.0,
+                    },
+                ),
+                ModeHud,
+                ThemedText::Text,
+            ));
+        });
+
+    commands
+        .spawn(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: font.0.clone(),
+                    font_size: 16.0,
+                    color: theme.text.0,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                ..default()
+            }),
+        )
+        .insert((FpsText, ThemedText::Text));
 }
 
-fn update_board(
-    mut score: ResMut<Score>,
-    mut reader: EventReader<AddScoreEvent>,
-    mut query: Query<&mut Text, With<Board>>,
+fn update_fps(
+    settings: Res<Settings>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut query: Query<(&mut Text, &mut Visibility), With<FpsText>>,
 ) {
-    if reader.read().next().is_some() {
-        score.increment();
+    for (mut text, mut visibility) in query.iter_mut() {
+        visibility.set_if_neq(if settings.show_fps {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if !settings.show_fps {
+            continue;
+        }
+        if let Some(fps) = diagnostics
+            .get(&FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(|fps| fps.smoothed())
+        {
+            text.sections[0].value = format!("FPS {:.0}", fps);
+        }
     }
+}
 
+fn update_mode_hud(
+    mode: Res<GameMode>,
+    clock: Res<ModeClock>,
+    localizer: Res<Localizer>,
+    mut query: Query<&mut Text, With<ModeHud>>,
+) {
+    let value = match *mode {
+        GameMode::Classic => String::new(),
+        GameMode::TimeAttack(_) => {
+            let remaining = clock.remaining(*mode).unwrap_or_default();
+            // 向上取整，剩余 0.3 秒时显示 1
+            let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+            localizer.get_with("hud-time-left", [("secs", secs.into())])
+        }
+        GameMode::Survival => {
+            localizer.get_with("hud-survived", [("secs", clock.elapsed.as_secs().into())])
+        }
+    };
     for mut text in query.iter_mut() {
-        text.sections[1].value = format!("{}", score.get());
+        if text.sections[0].value != value {
+            text.sections[0].value.clone_from(&value);
+        }
     }
 }
 
-fn reset_score(mut score: ResMut<Score>, mut query: Query<&mut Text, With<Board>>) {
+fn update_board(
+    score: Res<Score>,
+    combo: Res<Combo>,
+    rules: Res<ComboRules>,
+    localizer: Res<Localizer>,
+    mut query: Query<&mut Text, With<Board>>,
+) {
     for mut text in query.iter_mut() {
-        score.reset();
+        if localizer.is_changed() {
+            text.sections[0].value = localizer.get("score-label");
+            text.sections[2].value = localizer.get("streak-label");
+        }
         text.sections[1].value = format!("{}", score.get());
+        text.sections[3].value = match rules.multiplier(combo.streak) {
+            1 => format!("{}", combo.streak),
+            multiplier => format!("{} x{}", combo.streak, multiplier),
+        };
+    }
+}
+
+// 在被吃掉的食物位置显示 "+N x3"，存活加分不显示
+fn spawn_score_popups(
+    mut commands: Commands,
+    mut reader: EventReader<AddScoreEvent>,
+    layout: Res<Layout>,
+    theme: Res<Theme>,
+    font: Res<UiFont>,
+) {
+    for event in reader.read() {
+        let ScoreSource::Food(kind) = event.source else {
+            continue;
+        };
+        let value = match event.multiplier {
+            1 => format!("{:+}", event.points),
+            multiplier => format!("{:+} x{}", event.points, multiplier),
+        };
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 18.0,
+                        color: theme.food(kind),
+                    },
+                ),
+                transform: Transform::from_translation(
+                    layout.position_to_world(event.position).extend(1.0),
+                ),
+                ..default()
+            },
+            ScorePopup(Timer::from_seconds(0.8, TimerMode::Once)),
+        ));
+    }
+}
+
+// 分数向上飘并逐渐变透明，结束后移除
+fn animate_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in popups.iter_mut() {
+        if popup.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += POPUP_RISE_SPEED * time.delta_seconds();
+        let alpha = popup.0.fraction_remaining();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn cleanup_score_popups(mut commands: Commands, popups: Query<Entity, With<ScorePopup>>) {
+    for entity in popups.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_effects_hud(
+    effects: Res<ActiveEffects>,
+    registry: Res<PowerUpRegistry>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+    mut query: Query<&mut Text, With<EffectsHud>>,
+) {
+    for mut text in query.iter_mut() {
+        text.sections = effects
+            .0
+            .iter()
+            .map(|active| {
+                let def = &registry.0[active.def];
+                let stacks = match active.stacks {
+                    1 => String::new(),
+                    n => format!("x{} ", n),
+                };
+                TextSection::new(
+                    format!(
+                        "{} {}{:.1}s  ",
+                        localizer
+                            .try_get(&format!("powerup-{}", def.name))
+                            .unwrap_or_else(|| def.name.clone()),
+                        stacks,
+                        active.timer.remaining_secs()
+                    ),
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 20.0,
+                        color: def.color(),
+                    },
+                )
+            })
+            .collect();
     }
 }
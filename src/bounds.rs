@@ -0,0 +1,187 @@
+//! 可以活动的范围：一般是整个竞技场，缩圈的模式中每隔一段时间向内缩小一圈，圈外是危险区
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::actions::Position;
+use crate::enemy::Enemy;
+use crate::food::Food;
+use crate::gameover::{GameOverCause, GameOverEvent};
+use crate::mode::{reset_mode_clock, run_mode_rules, GameMode, ModeClock};
+use crate::obstacle::Obstacle;
+use crate::portal::Portal;
+use crate::powerup::PowerUp;
+use crate::settings::apply_arena;
+use crate::snake::SnakeHead;
+use crate::tick::{GameSet, GameTick};
+use crate::{Arena, GameState};
+
+pub struct BoundsPlugin;
+
+/// 当前可以活动的格子范围（包含两端），蛇头离开范围时游戏结束，食物只在范围内生成
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self::full(&Arena::default())
+    }
+}
+
+impl Bounds {
+    /// 整个竞技场
+    pub fn full(arena: &Arena) -> Self {
+        Self {
+            min: Position { x: 0, y: 0 },
+            max: Position {
+                x: arena.width as i32 - 1,
+                y: arena.height as i32 - 1,
+            },
+        }
+    }
+
+    /// 四边各向内缩小 `rings` 格
+    pub fn shrink(self, rings: u32) -> Self {
+        let rings = rings as i32;
+        Self {
+            min: Position {
+                x: self.min.x + rings,
+                y: self.min.y + rings,
+            },
+            max: Position {
+                x: self.max.x - rings,
+                y: self.max.y - rings,
+            },
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        (self.max.x - self.min.x + 1).max(0) as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.max.y - self.min.y + 1).max(0) as u32
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+    }
+
+    /// 范围内的所有格子，按列遍历
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        let (min, max) = (self.min, self.max);
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| Position { x, y }))
+    }
+
+    /// 穿墙模式下从范围的另一边出来
+    pub fn wrap(&self, pos: Position) -> Position {
+        Position {
+            x: self.min.x + (pos.x - self.min.x).rem_euclid(self.width() as i32),
+            y: self.min.y + (pos.y - self.min.y).rem_euclid(self.height() as i32),
+        }
+    }
+}
+
+/// 缩圈的时间表：每 `every` 秒缩小一圈，宽或高不会小于 `min_size`，`min_size` 为 0 时按 1 算
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct ShrinkSchedule {
+    pub every: f32,
+    pub min_size: u32,
+}
+
+impl Default for ShrinkSchedule {
+    fn default() -> Self {
+        Self {
+            every: 30.0,
+            min_size: 7,
+        }
+    }
+}
+
+impl ShrinkSchedule {
+    /// 开局 `elapsed` 之后已经缩小的圈数
+    pub fn rings(&self, elapsed: Duration, arena: &Arena) -> u32 {
+        // 至少留一格，否则 `Bounds::wrap` 会除以 0
+        let min_size = self.min_size.max(1);
+        let max_rings = arena.width.min(arena.height).saturating_sub(min_size) / 2;
+        ((elapsed.as_secs_f32() / self.every) as u32).min(max_rings)
+    }
+}
+
+impl Plugin for BoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bounds>()
+            .init_resource::<ShrinkSchedule>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                update_bounds.after(apply_arena).after(reset_mode_clock),
+            )
+            // 计时推进之后立即更新范围，下一个节拍使用新的范围
+            .add_systems(
+                FixedUpdate,
+                (
+                    update_bounds.after(run_mode_rules),
+                    (clear_outside_bounds, enter_danger_zone).after(update_bounds),
+                )
+                    .in_set(GameSet::Collide),
+            );
+    }
+}
+
+// 范围由竞技场大小和这一局进行的时间决定，读档后也能算出同样的范围
+pub fn update_bounds(
+    mode: Res<GameMode>,
+    clock: Res<ModeClock>,
+    schedule: Res<ShrinkSchedule>,
+    arena: Res<Arena>,
+    mut bounds: ResMut<Bounds>,
+) {
+    let rings = if mode.shrinks() {
+        schedule.rings(clock.elapsed, &arena)
+    } else {
+        0
+    };
+    bounds.set_if_neq(Bounds::full(&arena).shrink(rings));
+}
+
+// 缩圈后危险区里的食物、道具、障碍物和敌人都移除，传送门有一个在圈外时两个都移除
+fn clear_outside_bounds(
+    mut cmds: Commands,
+    bounds: Res<Bounds>,
+    items: Query<(Entity, &Position), Or<(With<Food>, With<PowerUp>, With<Obstacle>, With<Enemy>)>>,
+    portals: Query<(Entity, &Position, &Portal)>,
+) {
+    if !bounds.is_changed() {
+        return;
+    }
+    for (entity, pos) in items.iter() {
+        if !bounds.contains(*pos) {
+            cmds.entity(entity).despawn();
+        }
+    }
+    for (entity, pos, portal) in portals.iter() {
+        if !bounds.contains(*pos) || !bounds.contains(portal.partner) {
+            cmds.entity(entity).despawn();
+        }
+    }
+}
+
+// 缩圈时蛇头正好在被缩掉的一圈上，游戏结束；走进危险区在 `move_snake` 中判断
+fn enter_danger_zone(
+    bounds: Res<Bounds>,
+    tick: Res<GameTick>,
+    heads: Query<&Position, With<SnakeHead>>,
+    mut game_over: EventWriter<GameOverEvent>,
+) {
+    for &head in heads.iter().filter(|head| !bounds.contains(**head)) {
+        game_over.send(GameOverEvent {
+            cause: GameOverCause::DangerZone,
+            tick: tick.0,
+            position: head,
+        });
+    }
+}
@@ -0,0 +1,244 @@
+//! 画面效果：吃到食物时的粒子、死亡时蛇身散开、镜头抖动和闪屏，只影响画面不影响规则
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::layout::Layout;
+use crate::score::{AddScoreEvent, ScoreSource};
+use crate::snake::SnakeBlock;
+use crate::theme::Theme;
+use crate::{cleanup_game, GameState};
+
+pub struct EffectsPlugin;
+
+/// 各种效果的开关，为了无障碍可以全部关掉
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct EffectsSettings {
+    pub particles: bool,
+    /// 镜头抖动的强度，0 表示不抖动
+    pub shake: f32,
+    pub flash: bool,
+}
+
+impl Default for EffectsSettings {
+    fn default() -> Self {
+        Self {
+            particles: true,
+            shake: 1.0,
+            flash: true,
+        }
+    }
+}
+
+impl EffectsSettings {
+    pub fn off() -> Self {
+        Self {
+            particles: false,
+            shake: 0.0,
+            flash: false,
+        }
+    }
+}
+
+/// 飞散后淡出的小方块
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    life: Timer,
+}
+
+/// 镜头抖动的程度，0~1，随时间衰减
+#[derive(Resource, Default)]
+struct Trauma(f32);
+
+/// 抖动最大偏移（像素）
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+/// 每秒衰减的抖动程度
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// 盖住整个窗口、逐渐变透明的色块
+#[derive(Component)]
+struct Flash(Timer);
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EffectsSettings>()
+            .init_resource::<Trauma>()
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                scatter_snake.before(cleanup_game),
+            )
+            .add_systems(
+                Update,
+                (
+                    eat_burst.run_if(in_state(GameState::Playing)),
+                    update_particles,
+                    shake_camera,
+                    fade_flash,
+                ),
+            );
+    }
+}
+
+fn spawn_particle(
+    commands: &mut Commands,
+    at: Vec3,
+    size: f32,
+    color: Color,
+    velocity: Vec2,
+    secs: f32,
+) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite { color, ..default() },
+            transform: Transform::from_translation(at).with_scale(Vec3::new(size, size, 1.0)),
+            ..default()
+        },
+        Particle {
+            velocity,
+            life: Timer::from_seconds(secs, TimerMode::Once),
+        },
+    ));
+}
+
+fn spawn_flash(commands: &mut Commands, color: Color, secs: f32) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: color.with_alpha(0.5),
+                ..default()
+            },
+            // 足够盖住任何窗口
+            transform: Transform::from_xyz(0.0, 0.0, 50.0).with_scale(Vec3::splat(10000.0)),
+            ..default()
+        },
+        Flash(Timer::from_seconds(secs, TimerMode::Once)),
+    ));
+}
+
+// 吃到食物时在食物位置炸开一圈粒子，连击倍率越高粒子越多；扣分的食物闪一下屏
+fn eat_burst(
+    mut commands: Commands,
+    mut reader: EventReader<AddScoreEvent>,
+    settings: Res<EffectsSettings>,
+    layout: Res<Layout>,
+    theme: Res<Theme>,
+    mut trauma: ResMut<Trauma>,
+) {
+    // 粒子只是装饰，不使用 GameRng，以免影响固定种子的对局
+    let mut rng = rand::thread_rng();
+    for event in reader.read() {
+        let ScoreSource::Food(kind) = event.source else {
+            continue;
+        };
+        let color = theme.food(kind);
+        if settings.particles {
+            let at = layout.position_to_world(event.position).extend(2.0);
+            let count = 8 + 4 * event.multiplier.min(5);
+            for _ in 0..count {
+                let angle = rng.gen_range(0.0..TAU);
+                let speed = rng.gen_range(2.0..6.0) * layout.cell;
+                spawn_particle(
+                    &mut commands,
+                    at,
+                    layout.cell * 0.2,
+                    color,
+                    Vec2::from_angle(angle) * speed,
+                    rng.gen_range(0.3..0.6),
+                );
+            }
+        }
+        if event.points < 0 {
+            trauma.0 = (trauma.0 + 0.3).min(1.0);
+            if settings.flash {
+                spawn_flash(&mut commands, color, 0.2);
+            }
+        } else if event.multiplier > 1 {
+            trauma.0 = (trauma.0 + 0.1).min(1.0);
+        }
+    }
+}
+
+// 死亡时蛇身的每一节向四周散开，真正的蛇身随后在 cleanup_game 中被移除
+fn scatter_snake(
+    mut commands: Commands,
+    settings: Res<EffectsSettings>,
+    theme: Res<Theme>,
+    mut trauma: ResMut<Trauma>,
+    blocks: Query<(&Transform, &Sprite), With<SnakeBlock>>,
+) {
+    trauma.0 = 1.0;
+    if settings.flash {
+        spawn_flash(&mut commands, theme.accent.0, 0.4);
+    }
+    if !settings.particles {
+        return;
+    }
+    let mut rng = rand::thread_rng();
+    for (transform, sprite) in blocks.iter() {
+        let angle = rng.gen_range(0.0..TAU);
+        let speed = rng.gen_range(100.0..300.0);
+        spawn_particle(
+            &mut commands,
+            transform.translation.truncate().extend(2.0),
+            transform.scale.x,
+            sprite.color,
+            Vec2::from_angle(angle) * speed,
+            rng.gen_range(0.8..1.2),
+        );
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        if particle.life.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        particle.velocity *= 1.0 - 3.0 * dt.min(0.3);
+        sprite.color.set_alpha(particle.life.fraction_remaining());
+    }
+}
+
+// 偏移量和抖动程度的平方成正比，小的抖动几乎察觉不到
+fn shake_camera(
+    time: Res<Time>,
+    settings: Res<EffectsSettings>,
+    mut trauma: ResMut<Trauma>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    trauma.0 = (trauma.0 - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+    let strength = MAX_SHAKE_OFFSET * settings.shake * trauma.0 * trauma.0;
+    let mut rng = rand::thread_rng();
+    for mut transform in cameras.iter_mut() {
+        let offset = if strength > 0.0 {
+            Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * strength
+        } else {
+            Vec2::ZERO
+        };
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
+
+fn fade_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut Flash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in flashes.iter_mut() {
+        if flash.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(0.5 * flash.0.fraction_remaining());
+    }
+}
@@ -0,0 +1,325 @@
+//! 敌人：不受玩家控制的格子角色，和蛇用同一个节拍移动。
+//! 蛇头撞上任何敌人时游戏结束（在 `move_snake` 中判断），追击者走到蛇头上时也结束；
+//! 敌人不会走进蛇身、障碍物和传送门
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::control::GameControl;
+use crate::actions::{Position, Size};
+use crate::bounds::Bounds;
+use crate::food::Food;
+use crate::gameover::{GameOverCause, GameOverEvent};
+use crate::obstacle::random_obstacle_cell;
+use crate::portal::spawn_portals;
+use crate::settings::apply_arena;
+use crate::snake::{spawn_snake, SnakeHead};
+use crate::tick::{GameSet, GameTick};
+use crate::{Arena, GameRng, GameState};
+
+pub struct EnemyPlugin;
+
+#[derive(Component, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Enemy {
+    pub kind: EnemyKind,
+    /// 还要等蛇移动几次才走下一步
+    pub wait: u32,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// 沿 `path` 来回走，`index` 是当前所在的格子，走到头或被挡住时掉头
+    Patrol {
+        path: Vec<Position>,
+        index: usize,
+        forward: bool,
+    },
+    /// 随机走动，吃掉走到的食物
+    Wanderer,
+    /// 沿最短路线追蛇头
+    Chaser,
+}
+
+impl EnemyKind {
+    /// 蛇每移动几次它移动一次
+    pub fn pace(&self) -> u32 {
+        match self {
+            Self::Patrol { .. } => 1,
+            Self::Wanderer | Self::Chaser => 2,
+        }
+    }
+
+    fn size(&self) -> f32 {
+        match self {
+            Self::Patrol { .. } => 1.0,
+            Self::Wanderer => 0.6,
+            Self::Chaser => 0.8,
+        }
+    }
+}
+
+impl Enemy {
+    /// 刚生成的敌人先等一轮再走
+    pub fn new(kind: EnemyKind) -> Self {
+        Self {
+            wait: kind.pace(),
+            kind,
+        }
+    }
+}
+
+/// 随机生成的巡逻路线的格数
+pub const PATROL_LENGTH: usize = 5;
+
+/// 在设置中打开敌人时每种敌人随机生成的个数
+pub const RANDOM_ENEMIES: u32 = 1;
+
+/// 每局开始时生成的敌人：`patrols` 是固定路线的巡逻块，其余的随机放置
+#[derive(Resource, Clone, Default, PartialEq, Debug)]
+pub struct EnemyConfig {
+    /// 每条路线上相邻的两个格子要挨着
+    pub patrols: Vec<Vec<Position>>,
+    pub random_patrols: u32,
+    pub wanderers: u32,
+    pub chasers: u32,
+}
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemyConfig>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_enemies
+                    .after(spawn_snake)
+                    .after(apply_arena)
+                    .after(spawn_portals),
+            )
+            .add_systems(FixedUpdate, move_enemies.in_set(GameSet::Collide))
+            .add_systems(OnEnter(GameState::GameOver), cleanup_enemies)
+            .add_systems(OnEnter(GameState::Menu), cleanup_enemies);
+    }
+}
+
+pub fn spawn_enemy_at(cmds: &mut Commands, enemy: Enemy, pos: Position) -> Entity {
+    let size = enemy.kind.size();
+    cmds.spawn((enemy, pos, Size::square(size))).id()
+}
+
+// 先放固定路线的巡逻块，再随机放，随机位置取自 GameRng；和障碍物一样不放在蛇头附近
+pub fn spawn_enemies(
+    mut cmds: Commands,
+    config: Res<EnemyConfig>,
+    arena: Res<Arena>,
+    mut rng: ResMut<GameRng>,
+    heads: Query<&Position, With<SnakeHead>>,
+    occupied: Query<&Position>,
+) {
+    // 开局时还没有缩圈
+    let bounds = Bounds::full(&arena);
+    let mut occupied = occupied.iter().copied().collect::<HashSet<_>>();
+    for path in &config.patrols {
+        let usable = path
+            .iter()
+            .all(|pos| bounds.contains(*pos) && !occupied.contains(pos));
+        if path.is_empty() || !usable {
+            warn!("巡逻路线 {:?} 不可用，跳过", path);
+            continue;
+        }
+        spawn_patrol(&mut cmds, path.clone(), &mut occupied);
+    }
+    let Ok(&head) = heads.get_single() else {
+        return;
+    };
+    for _ in 0..config.random_patrols {
+        if let Some(path) = random_patrol_path(&mut rng, &bounds, &occupied, head) {
+            spawn_patrol(&mut cmds, path, &mut occupied);
+        }
+    }
+    let others = std::iter::repeat_n(EnemyKind::Wanderer, config.wanderers as usize).chain(
+        std::iter::repeat_n(EnemyKind::Chaser, config.chasers as usize),
+    );
+    for kind in others {
+        let Some(pos) = random_obstacle_cell(&mut rng, &bounds, &occupied, head) else {
+            return;
+        };
+        spawn_enemy_at(&mut cmds, Enemy::new(kind), pos);
+        occupied.insert(pos);
+    }
+}
+
+fn spawn_patrol(cmds: &mut Commands, path: Vec<Position>, occupied: &mut HashSet<Position>) {
+    let start = path[0];
+    let kind = EnemyKind::Patrol {
+        path,
+        index: 0,
+        forward: true,
+    };
+    spawn_enemy_at(cmds, Enemy::new(kind), start);
+    occupied.insert(start);
+}
+
+// 从一个随机的空格子出发，沿随机方向取一段直线，整段都空着才用
+fn random_patrol_path(
+    rng: &mut GameRng,
+    bounds: &Bounds,
+    occupied: &HashSet<Position>,
+    head: Position,
+) -> Option<Vec<Position>> {
+    for _ in 0..20 {
+        let start = random_obstacle_cell(rng, bounds, occupied, head)?;
+        let direction = GameControl::ALL[rng.gen_range(0..GameControl::ALL.len())];
+        let path = std::iter::successors(Some(start), |&pos| Some(direction.step(pos)))
+            .take(PATROL_LENGTH)
+            .collect::<Vec<_>>();
+        if path
+            .iter()
+            .all(|pos| bounds.contains(*pos) && !occupied.contains(pos))
+        {
+            return Some(path);
+        }
+    }
+    None
+}
+
+// 每个节拍敌人按各自的节奏走一步；按位置顺序处理，和实体的生成顺序无关
+fn move_enemies(
+    mut cmds: Commands,
+    bounds: Res<Bounds>,
+    mut rng: ResMut<GameRng>,
+    tick: Res<GameTick>,
+    mut game_over: EventWriter<GameOverEvent>,
+    mut enemies: Query<(Entity, &mut Enemy, &mut Position)>,
+    others: Query<(Entity, &Position, Has<Food>, Has<SnakeHead>), Without<Enemy>>,
+) {
+    let mut solid = HashSet::new();
+    let mut food = HashMap::new();
+    let mut head = None;
+    for (entity, &pos, is_food, is_head) in others.iter() {
+        if is_food {
+            food.insert(pos, entity);
+        } else {
+            solid.insert(pos);
+        }
+        if is_head {
+            head = Some(pos);
+        }
+    }
+    let mut taken = enemies
+        .iter()
+        .map(|(_, _, pos)| *pos)
+        .collect::<HashSet<_>>();
+    let mut order = enemies
+        .iter()
+        .map(|(entity, _, pos)| (pos.x, pos.y, entity))
+        .collect::<Vec<_>>();
+    order.sort();
+
+    for (_, _, entity) in order {
+        let Ok((_, mut enemy, mut pos)) = enemies.get_mut(entity) else {
+            continue;
+        };
+        if enemy.wait > 0 {
+            enemy.wait -= 1;
+            continue;
+        }
+        enemy.wait = enemy.kind.pace() - 1;
+        // 除了游荡者，敌人也不走进有食物的格子
+        let open = |cell: Position, eats: bool| {
+            bounds.contains(cell)
+                && !solid.contains(&cell)
+                && !taken.contains(&cell)
+                && (eats || !food.contains_key(&cell))
+        };
+        let next = match &mut enemy.kind {
+            EnemyKind::Patrol {
+                path,
+                index,
+                forward,
+            } => {
+                let target = if *forward {
+                    path.get(*index + 1)
+                } else {
+                    index.checked_sub(1).and_then(|i| path.get(i))
+                };
+                match target {
+                    Some(&cell) if open(cell, false) => {
+                        if *forward {
+                            *index += 1;
+                        } else {
+                            *index -= 1;
+                        }
+                        Some(cell)
+                    }
+                    _ => {
+                        *forward = !*forward;
+                        None
+                    }
+                }
+            }
+            EnemyKind::Wanderer => {
+                let choices = GameControl::ALL
+                    .map(|direction| direction.step(*pos))
+                    .into_iter()
+                    .filter(|&cell| open(cell, true))
+                    .collect::<Vec<_>>();
+                (!choices.is_empty()).then(|| choices[rng.gen_range(0..choices.len())])
+            }
+            EnemyKind::Chaser => {
+                let Some(head) = head else {
+                    continue;
+                };
+                let next = chase_step(*pos, head, |cell| open(cell, false));
+                if next == Some(head) {
+                    game_over.send(GameOverEvent {
+                        cause: GameOverCause::Enemy,
+                        tick: tick.0,
+                        position: head,
+                    });
+                }
+                next
+            }
+        };
+        let Some(next) = next else {
+            continue;
+        };
+        if let Some(entity) = food.remove(&next) {
+            cmds.entity(entity).despawn();
+        }
+        taken.remove(&*pos);
+        taken.insert(next);
+        *pos = next;
+    }
+}
+
+/// 从 `from` 走向 `target` 的最短路线的第一步，`open` 判断格子能不能走；走不到时返回 `None`
+pub fn chase_step(
+    from: Position,
+    target: Position,
+    open: impl Fn(Position) -> bool,
+) -> Option<Position> {
+    // 从目标往回搜，先碰到的 from 的邻居就是下一步
+    let mut visited = HashSet::from([target]);
+    let mut queue = VecDeque::from([target]);
+    while let Some(cell) = queue.pop_front() {
+        for direction in GameControl::ALL {
+            let next = direction.step(cell);
+            if next == from {
+                return Some(cell);
+            }
+            if open(next) && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn cleanup_enemies(mut cmds: Commands, enemies: Query<Entity, With<Enemy>>) {
+    for entity in enemies.iter() {
+        cmds.entity(entity).despawn();
+    }
+}
@@ -1,13 +1,127 @@
 use crate::actions::{Position, Size};
-use crate::{GameState, ARENA_HEIGHT, ARENA_WIDTH, FOOD_COLOR};
+use crate::bounds::Bounds;
+use crate::tick::GameSet;
+use crate::GameRng;
 use bevy::prelude::*;
-use rand::random;
+use bevy::utils::HashSet;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 
 pub struct FoodPlugin;
 
 #[derive(Component)]
-pub struct Food;
+pub struct Food(pub FoodKind);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub enum FoodKind {
+    Normal,
+    /// 分数高
+    Golden,
+    /// 很快消失，会闪烁
+    Rare,
+    /// 扣分并让蛇变短
+    Poison,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 4] = [Self::Normal, Self::Golden, Self::Rare, Self::Poison];
+
+    /// 生成权重
+    pub fn weight(self) -> u32 {
+        match self {
+            Self::Normal => 70,
+            Self::Golden => 10,
+            Self::Rare => 12,
+            Self::Poison => 8,
+        }
+    }
+
+    pub fn score(self) -> i32 {
+        match self {
+            Self::Normal => 1,
+            Self::Golden => 5,
+            Self::Rare => 3,
+            Self::Poison => -2,
+        }
+    }
+
+    /// 吃掉后增加的节数，负数表示变短
+    pub fn growth(self) -> i32 {
+        match self {
+            Self::Poison => -1,
+            _ => 1,
+        }
+    }
+
+    /// 这种食物自己的存在秒数，`None` 表示不会消失
+    pub fn lifetime(self) -> Option<f32> {
+        match self {
+            Self::Rare => Some(5.0),
+            _ => None,
+        }
+    }
+
+    pub fn blinks(self) -> bool {
+        self == Self::Rare
+    }
+}
+
+/// 会消失的食物的剩余时间
+#[derive(Component)]
+pub struct FoodLifetime(pub Timer);
+
+/// 食物在消失前这么多秒开始淡出
+pub const FOOD_FADE_SECS: f32 = 1.0;
+
+impl FoodLifetime {
+    /// 闪烁的食物这一帧是否显示
+    pub fn blink_visible(&self) -> bool {
+        ((self.0.elapsed_secs() * 4.0) as u32).is_multiple_of(2)
+    }
+
+    /// 淡出时的不透明度，还没开始淡出时为 1
+    pub fn fade(&self) -> f32 {
+        (self.0.remaining_secs() / FOOD_FADE_SECS).min(1.0)
+    }
+}
+
+/// 食物的生成方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FoodSpawnMode {
+    /// 场上总是正好有一个食物，被吃掉或消失后立即补上
+    Classic,
+    /// 按 `FoodSpawnTimer` 定时生成，最多 `FoodConfig::max_food` 个
+    Timer,
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct FoodConfig {
+    pub mode: FoodSpawnMode,
+    pub max_food: usize,
+    /// 食物存在的秒数，`None` 表示不会消失；自己有存在时间的食物取较短的一个
+    pub expiry: Option<f32>,
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        Self {
+            mode: FoodSpawnMode::Timer,
+            max_food: 5,
+            expiry: None,
+        }
+    }
+}
+
+impl FoodConfig {
+    pub fn lifetime_of(&self, kind: FoodKind) -> Option<f32> {
+        match (kind.lifetime(), self.expiry) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
 
 #[derive(Resource)]
 pub struct FoodSpawnTimer(pub Timer);
@@ -24,25 +138,85 @@ impl FoodSpawnTimer {
 impl Plugin for FoodPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(FoodSpawnTimer::new(2.0))
-        .add_systems(Update,spawn_food.run_if(in_state(GameState::Playing)));
-    }
-}
-
-pub fn spawn_food(mut cmds: Commands, time: Res<Time>, mut timer: ResMut<FoodSpawnTimer>) {
-  if !timer.0.tick(time.delta()).finished() {
-      return;
-  }
-  cmds.spawn(SpriteBundle {
-      sprite: Sprite {
-          color: FOOD_COLOR,
-          ..default()
-      },
-      ..default()
-  })
-  .insert(Food)
-  .insert(Position {
-      x: (random::<f32>() * ARENA_WIDTH as f32) as i32,
-      y: (random::<f32>() * ARENA_HEIGHT as f32) as i32,
-  })
-  .insert(Size::square(0.8));
-}
\ No newline at end of file
+        .init_resource::<FoodConfig>()
+        .add_systems(
+            FixedUpdate,
+            // 吃掉和消失的食物先清理，经典模式才能在同一帧补上
+            (expire_food, spawn_food).chain().in_set(GameSet::Spawn),
+        );
+    }
+}
+
+pub fn spawn_food(
+    mut cmds: Commands,
+    time: Res<Time>,
+    config: Res<FoodConfig>,
+    mut timer: ResMut<FoodSpawnTimer>,
+    mut rng: ResMut<GameRng>,
+    bounds: Res<Bounds>,
+    food: Query<(), With<Food>>,
+    occupied: Query<&Position>,
+) {
+    let count = match config.mode {
+        FoodSpawnMode::Classic => 1usize.saturating_sub(food.iter().len()),
+        FoodSpawnMode::Timer => {
+            let finished = timer.0.tick(time.delta()).finished();
+            usize::from(finished && food.iter().len() < config.max_food)
+        }
+    };
+    if count == 0 {
+        return;
+    }
+    let weights = WeightedIndex::new(FoodKind::ALL.map(FoodKind::weight)).unwrap();
+    let mut occupied = occupied.iter().copied().collect::<HashSet<_>>();
+    for _ in 0..count {
+        let Some(pos) = random_free_cell(&mut rng, &bounds, &occupied) else {
+            return;
+        };
+        let kind = FoodKind::ALL[weights.sample(&mut rng.0)];
+        spawn_food_at(&mut cmds, kind, pos, config.lifetime_of(kind));
+        occupied.insert(pos);
+    }
+}
+
+/// 在活动范围内随机选一个没有被占用的格子，范围内满了时返回 `None`
+pub fn random_free_cell(
+    rng: &mut GameRng,
+    bounds: &Bounds,
+    occupied: &HashSet<Position>,
+) -> Option<Position> {
+    let free = bounds
+        .cells()
+        .filter(|pos| !occupied.contains(pos))
+        .collect::<Vec<_>>();
+    if free.is_empty() {
+        return None;
+    }
+    Some(free[rng.gen_range(0..free.len())])
+}
+
+pub fn spawn_food_at(
+    cmds: &mut Commands,
+    kind: FoodKind,
+    pos: Position,
+    lifetime: Option<f32>,
+) -> Entity {
+    let mut food = cmds.spawn((Food(kind), pos, Size::square(0.8)));
+    if let Some(secs) = lifetime {
+        food.insert(FoodLifetime(Timer::from_seconds(secs, TimerMode::Once)));
+    }
+    food.id()
+}
+
+// 到时间的食物消失
+pub fn expire_food(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut food: Query<(Entity, &mut FoodLifetime)>,
+) {
+    for (entity, mut lifetime) in food.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            cmds.entity(entity).despawn();
+        }
+    }
+}
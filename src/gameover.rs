@@ -0,0 +1,90 @@
+//! 游戏结束：各条规则判定这一局结束时发出 `GameOverEvent`，由这里统一记录原因并切换状态
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::Position;
+use crate::GameState;
+
+pub struct GameOverPlugin;
+
+/// 这一局结束的原因
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum GameOverCause {
+    /// 撞墙
+    Wall,
+    /// 撞到自己
+    Body,
+    Obstacle,
+    /// 撞上敌人或被追击者追上
+    Enemy,
+    /// 走进或被缩圈留在危险区
+    DangerZone,
+    /// 限时模式时间到
+    Timeout,
+}
+
+impl GameOverCause {
+    pub const ALL: [GameOverCause; 6] = [
+        Self::Wall,
+        Self::Body,
+        Self::Obstacle,
+        Self::Enemy,
+        Self::DangerZone,
+        Self::Timeout,
+    ];
+
+    /// 本地化文本的键名
+    pub fn key(self) -> String {
+        format!("cause-{:?}", self)
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GameOverEvent {
+    pub cause: GameOverCause,
+    /// 结束时的节拍，见 `GameTick`
+    pub tick: u64,
+    /// 出事的格子：撞墙时是墙外的格子，撞到东西时是那个东西所在的格子，其他情况是蛇头
+    pub position: Position,
+}
+
+/// 上一局结束的原因，开始新的一局时清空
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LastGameOver(pub Option<GameOverEvent>);
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameOverEvent>()
+            .init_resource::<LastGameOver>()
+            .add_systems(OnEnter(GameState::Playing), reset_game_over)
+            .add_systems(
+                FixedPostUpdate,
+                end_game.run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+fn reset_game_over(mut last: ResMut<LastGameOver>) {
+    last.0 = None;
+}
+
+// 同一个节拍有多个原因时取最先发出的；结束的节拍之后这一帧不再模拟，状态在下一帧切换
+fn end_game(
+    mut events: EventReader<GameOverEvent>,
+    mut last: ResMut<LastGameOver>,
+    mut time: ResMut<Time<Fixed>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(&event) = events.read().next() else {
+        return;
+    };
+    events.clear();
+    if last.0.is_some() {
+        return;
+    }
+    last.0 = Some(event);
+    next_state.set(GameState::GameOver);
+    let overstep = time.overstep();
+    time.discard_overstep(overstep);
+}
@@ -0,0 +1,137 @@
+//! 排行榜：每局结束时记录分数，每种模式一张表，保存在数据目录下的 `highscores.ron`
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::mode::GameMode;
+use crate::score::{reset_score, Combo, Score};
+use crate::settings::DataDir;
+use crate::GameState;
+
+pub struct HighScorePlugin;
+
+/// 每张排行榜保留的条数
+pub const MAX_HIGH_SCORES: usize = 10;
+
+const HIGH_SCORE_FILE: &str = "highscores.ron";
+
+/// 读不出来的排行榜改成这个名字留着，免得下一局结束时被空表覆盖
+pub const BROKEN_HIGH_SCORE_FILE: &str = "highscores.broken.ron";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub best_streak: u32,
+}
+
+/// 每种模式从高到低排列的最高分
+#[derive(Resource, Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HighScores(BTreeMap<GameMode, Vec<HighScore>>);
+
+impl HighScores {
+    pub fn entries(&self, mode: GameMode) -> &[HighScore] {
+        self.0.get(&mode).map_or(&[], Vec::as_slice)
+    }
+
+    /// 记录一局的成绩，进入排行榜时返回名次（从 0 开始）；0 分不记录
+    pub fn insert(&mut self, mode: GameMode, entry: HighScore) -> Option<usize> {
+        if entry.score == 0 {
+            return None;
+        }
+        let table = self.0.entry(mode).or_default();
+        // 同分时先达到的排在前面
+        let rank = table.partition_point(|other| other.score >= entry.score);
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        table.insert(rank, entry);
+        table.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    /// 文件不存在或读取失败时返回空的排行榜；格式错误时还会把文件改名为
+    /// `BROKEN_HIGH_SCORE_FILE` 留在同一目录下
+    pub fn load(path: &Path) -> Self {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Self::default(),
+            // 不是 UTF-8，和格式错误一样处理
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                set_aside(path, &err);
+                return Self::default();
+            }
+            Err(err) => {
+                warn!("读取排行榜失败：{}", err);
+                return Self::default();
+            }
+        };
+        ron::from_str(&source).unwrap_or_else(|err| {
+            set_aside(path, &err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let source = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        std::fs::write(path, source)
+    }
+}
+
+fn set_aside(path: &Path, err: &dyn std::fmt::Display) {
+    let broken = path.with_file_name(BROKEN_HIGH_SCORE_FILE);
+    warn!(
+        "排行榜文件格式错误：{}，改名为 {} 并使用空的排行榜",
+        err,
+        broken.display()
+    );
+    if let Err(err) = std::fs::rename(path, &broken) {
+        warn!("移走排行榜文件失败：{}", err);
+    }
+}
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScores>()
+            .add_systems(Startup, load_high_scores)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                record_high_score.before(reset_score),
+            );
+    }
+}
+
+fn load_high_scores(dir: Res<DataDir>, mut scores: ResMut<HighScores>) {
+    if let Some(path) = dir.file(HIGH_SCORE_FILE) {
+        *scores = HighScores::load(&path);
+    }
+}
+
+// 分数在进入结束界面时清零，要在那之前记录
+fn record_high_score(
+    score: Res<Score>,
+    combo: Res<Combo>,
+    mode: Res<GameMode>,
+    dir: Res<DataDir>,
+    mut scores: ResMut<HighScores>,
+) {
+    let entry = HighScore {
+        score: score.get(),
+        best_streak: combo.best,
+    };
+    if scores.insert(*mode, entry).is_none() {
+        return;
+    }
+    if let Some(path) = dir.file(HIGH_SCORE_FILE) {
+        if let Err(err) = scores.save(&path) {
+            warn!("保存排行榜失败：{}", err);
+        }
+    }
+}
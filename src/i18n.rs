@@ -0,0 +1,151 @@
+//! 界面文本的本地化，文本按键名从 `assets/i18n/*.ftl`（Fluent 格式）中查找
+
+use bevy::prelude::*;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_syntax::ast::Entry;
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Self::English, Self::Chinese];
+
+    fn id(self) -> LanguageIdentifier {
+        match self {
+            Self::English => "en".parse().unwrap(),
+            Self::Chinese => "zh".parse().unwrap(),
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::English => include_str!("../assets/i18n/en.ftl"),
+            Self::Chinese => include_str!("../assets/i18n/zh.ftl"),
+        }
+    }
+
+    /// 这种语言定义了的所有键名
+    pub fn keys(self) -> Vec<String> {
+        let resource =
+            FluentResource::try_new(self.source().to_string()).unwrap_or_else(|(r, _)| r);
+        resource
+            .entries()
+            .filter_map(|entry| match entry {
+                Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 用这种语言自己写的名字，显示在语言选项上
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Chinese => "中文",
+        }
+    }
+
+    /// 列表中的下一种语言，到末尾后回到第一种
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&lang| lang == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// 当前语言和所有语言的文本，找不到的键先回退到英文，再回退到键名本身
+#[derive(Resource)]
+pub struct Localizer {
+    language: Language,
+    bundles: Vec<(Language, FluentBundle<FluentResource>)>,
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+impl Localizer {
+    pub fn new(language: Language) -> Self {
+        let bundles = Language::ALL
+            .iter()
+            .map(|&lang| {
+                let resource = FluentResource::try_new(lang.source().to_string())
+                    .unwrap_or_else(|(_, errors)| panic!("{:?} 文本格式错误：{:?}", lang, errors));
+                let mut bundle = FluentBundle::new_concurrent(vec![lang.id()]);
+                // 不插入双向文本的隔离字符，bevy 的字体渲染不认识它们
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|errors| panic!("{:?} 文本键名重复：{:?}", lang, errors));
+                (lang, bundle)
+            })
+            .collect();
+        Self { language, bundles }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        self.language = language;
+    }
+
+    fn bundle(&self, language: Language) -> &FluentBundle<FluentResource> {
+        &self
+            .bundles
+            .iter()
+            .find(|(lang, _)| *lang == language)
+            .unwrap()
+            .1
+    }
+
+    pub fn get(&self, key: &str) -> String {
+        self.format(key, None)
+    }
+
+    /// 和 `get` 相同，但找不到键时返回 `None`
+    pub fn try_get(&self, key: &str) -> Option<String> {
+        self.has(key).then(|| self.get(key))
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        [self.language, Language::English]
+            .iter()
+            .any(|&language| self.bundle(language).has_message(key))
+    }
+
+    pub fn get_with<'a>(
+        &self,
+        key: &str,
+        args: impl IntoIterator<Item = (&'a str, FluentValue<'a>)>,
+    ) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(name, value);
+        }
+        self.format(key, Some(&fluent_args))
+    }
+
+    fn format(&self, key: &str, args: Option<&FluentArgs>) -> String {
+        for language in [self.language, Language::English] {
+            let bundle = self.bundle(language);
+            let Some(pattern) = bundle.get_message(key).and_then(|message| message.value()) else {
+                continue;
+            };
+            let mut errors = vec![];
+            return bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned();
+        }
+        key.to_string()
+    }
+}
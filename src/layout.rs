@@ -0,0 +1,44 @@
+//! 竞技场在窗口中的布局：格子保持正方形，顶部留出记分牌的位置，其余空白处居中留边
+
+use bevy::math::Vec2;
+use bevy::prelude::Resource;
+
+use crate::actions::Position;
+use crate::Arena;
+
+/// 顶部记分牌占用的高度
+pub const HUD_HEIGHT: f32 = 40.0;
+
+/// 竞技场在窗口中的位置，坐标原点在窗口中心，y 轴向上（和 2D 相机一致）
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub struct Layout {
+    /// 每个格子的边长
+    pub cell: f32,
+    /// 竞技场的中心
+    pub center: Vec2,
+    /// 竞技场的宽高
+    pub size: Vec2,
+}
+
+impl Layout {
+    /// 在 `window` 大小的窗口中放下 `arena`，顶部留出 `hud_height`
+    pub fn new(window: Vec2, arena: &Arena, hud_height: f32) -> Self {
+        let hud_height = hud_height.clamp(0.0, window.y.max(0.0));
+        let available = Vec2::new(window.x, window.y - hud_height).max(Vec2::ZERO);
+        let cell = (available.x / arena.width as f32).min(available.y / arena.height as f32);
+        Self {
+            cell,
+            center: Vec2::new(0.0, -hud_height / 2.0),
+            size: Vec2::new(arena.width as f32, arena.height as f32) * cell,
+        }
+    }
+
+    /// 格子中心的坐标，格子坐标可以是小数（用于两个格子之间的插值）
+    pub fn cell_to_world(&self, cell: Vec2) -> Vec2 {
+        self.center - self.size / 2.0 + (cell + 0.5) * self.cell
+    }
+
+    pub fn position_to_world(&self, pos: Position) -> Vec2 {
+        self.cell_to_world(Vec2::new(pos.x as f32, pos.y as f32))
+    }
+}
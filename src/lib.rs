@@ -1,49 +1,206 @@
-use actions::ActionsPlugin;
-use bevy::{app::{App, Plugin}, color::Color, prelude::{AppExtStates,States}};
-use board::BoardPlugin;
-use food::FoodPlugin;
-use menu::MenuPlugin;
-use snake::SnakePlugin;
-use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::{app::{App, Plugin}, prelude::{AppExtStates,States,SubStates}};
+use bevy::prelude::{Commands, Deref, DerefMut, Entity, OnEnter, OnExit, Query, ResMut, Resource, StateSet, Time, Virtual, With};
+use actions::Position;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use food::{Food, FoodPlugin};
+use highscore::HighScorePlugin;
+use powerup::PowerUpPlugin;
+use score::ScorePlugin;
+use settings::SettingsPlugin;
+use serde::{Deserialize, Serialize};
+use snake::{SnakeBlock, SnakePlugin};
 
+pub mod snake;
+pub mod food;
+pub mod score;
+pub mod powerup;
+pub mod actions;
+pub mod layout;
+pub mod theme;
+pub mod i18n;
+pub mod settings;
+pub mod highscore;
+pub mod mode;
+pub mod obstacle;
+pub mod bounds;
+pub mod portal;
+pub mod enemy;
+pub mod gameover;
+pub mod tick;
+pub mod save;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "render")]
+pub mod effects;
+#[cfg(feature = "ui")]
 mod menu;
-mod snake;
-mod food;
+#[cfg(feature = "ui")]
 mod board;
-mod actions;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 pub const ARENA_WIDTH: u32 = 25;
 pub const ARENA_HEIGHT: u32 = 25;
 
-pub const BACKGROUND_COLOR: Color = Color::srgb(0.04, 0.04, 0.04);
-pub const SNAKE_HEAD_COLOR: Color = Color::srgb(0.7, 0.7, 0.7);
-pub const SNAKE_BODY_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
-pub const FOOD_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
-
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
-enum GameState {
+pub enum GameState {
     #[default]
     Menu,
     Playing,
     GameOver,
 }
 
+/// 游戏中是否暂停，只在 `GameState::Playing` 时存在
+#[derive(SubStates, Default, Clone, Eq, PartialEq, Debug, Hash)]
+#[source(GameState = GameState::Playing)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// 竞技场的格子数，宽高可以不同，默认为 `ARENA_WIDTH` x `ARENA_HEIGHT`
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Arena {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self {
+            width: ARENA_WIDTH,
+            height: ARENA_HEIGHT,
+        }
+    }
+}
+
+impl Arena {
+    pub fn contains(&self, pos: Position) -> bool {
+        (0..self.width as i32).contains(&pos.x) && (0..self.height as i32).contains(&pos.y)
+    }
+
+    /// 所有格子，按列遍历
+    pub fn cells(&self) -> impl Iterator<Item = Position> {
+        let height = self.height as i32;
+        (0..self.width as i32).flat_map(move |x| (0..height).map(move |y| Position { x, y }))
+    }
+}
+
+/// 游戏内所有随机数都从这里取，固定种子后同样的输入会得到同样的一局；
+/// 和 `StdRng` 是同一种算法，但状态可以保存到存档中
+#[derive(Resource, Deref, DerefMut, Clone, Serialize, Deserialize)]
+pub struct GameRng(pub ChaCha12Rng);
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(ChaCha12Rng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(ChaCha12Rng::from_entropy())
+    }
+}
+
+/// 根据启用的 cargo feature 添加对应的插件，规则部分总是启用
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>().add_plugins((
-            ActionsPlugin,
-            MenuPlugin,
-            BoardPlugin,
-            SnakePlugin,
-            FoodPlugin
-        ));
+        app.add_plugins((LogicPlugin, theme::ThemePlugin))
+            .add_plugins(bevy::diagnostic::FrameTimeDiagnosticsPlugin);
+        // 界面语言取自设置，设置要在添加 GamePlugin 之前插入
+        let language = app.world().resource::<settings::Settings>().language;
+        app.insert_resource(i18n::Localizer::new(language));
+
+        #[cfg(feature = "render")]
+        app.add_plugins((render::RenderPlugin, effects::EffectsPlugin));
+
+        #[cfg(feature = "ui")]
+        app.add_plugins((menu::MenuPlugin, board::BoardPlugin));
 
         #[cfg(debug_assertions)]
         {
-            app.add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+            use bevy::diagnostic::LogDiagnosticsPlugin;
+            app.add_plugins(LogDiagnosticsPlugin::default());
         }
     }
-}
\ No newline at end of file
+}
+
+/// 游戏规则部分（状态、蛇、食物、道具、计分），不依赖窗口，可供其他前端复用
+pub struct LogicPlugin;
+
+impl Plugin for LogicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_sub_state::<PauseState>()
+            .init_resource::<GameRng>()
+            .init_resource::<Arena>()
+            .init_resource::<mode::GameMode>()
+            .add_plugins((ScorePlugin, SnakePlugin, FoodPlugin, PowerUpPlugin, SettingsPlugin))
+            .add_plugins((
+                HighScorePlugin,
+                save::SavePlugin,
+                mode::ModePlugin,
+                obstacle::ObstaclePlugin,
+                bounds::BoundsPlugin,
+                portal::PortalPlugin,
+                enemy::EnemyPlugin,
+                tick::TickPlugin,
+                gameover::GameOverPlugin,
+            ))
+            // 从暂停界面回到主菜单时也要清理
+            .add_systems(OnEnter(GameState::GameOver), cleanup_game)
+            .add_systems(OnEnter(GameState::Menu), cleanup_game)
+            .add_systems(OnEnter(PauseState::Paused), pause_time)
+            .add_systems(OnExit(PauseState::Paused), resume_time);
+    }
+}
+
+// 暂停时停止游戏时间，所有计时器随之停下
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// 不开窗口运行游戏：最小的 bevy 运行时加上游戏规则，按键需要由调用方写入 `ButtonInput<KeyCode>`
+#[cfg(feature = "headless")]
+pub struct HeadlessPlugin;
+
+#[cfg(feature = "headless")]
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        use bevy::app::{PluginGroup, ScheduleRunnerPlugin};
+        use bevy::input::{keyboard::KeyCode, ButtonInput};
+        use bevy::state::app::StatesPlugin;
+        use bevy::MinimalPlugins;
+        use std::time::Duration;
+
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+                1.0 / 60.0,
+            ))),
+            StatesPlugin,
+        ))
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_plugins(LogicPlugin);
+    }
+}
+
+// 游戏结束时清理蛇和食物
+pub(crate) fn cleanup_game(
+    mut cmds: Commands,
+    food: Query<Entity, With<Food>>,
+    blocks: Query<Entity, With<SnakeBlock>>,
+) {
+    for entity in food.iter().chain(blocks.iter()) {
+        cmds.entity(entity).despawn();
+    }
+}
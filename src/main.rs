@@ -1,19 +1,24 @@
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
+use snake::settings::{DataDir, SettingsFile};
 use snake::GamePlugin;
-use snake::BACKGROUND_COLOR;
 
 fn main() {
+    // 设置要在 GamePlugin 之前插入，主题和语言在构建插件时就要用到
+    let file = SettingsFile::user();
+    let settings = file.load();
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "贪吃蛇demo".to_string(),
-                resolution: WindowResolution::new(500.0, 500.0),
+                resolution: WindowResolution::new(500.0, 540.0),
                 ..default()
             }),
             ..default()
         }))
+        .insert_resource(file)
+        .insert_resource(DataDir::user())
+        .insert_resource(settings)
         .add_plugins(GamePlugin)
-        .insert_resource(ClearColor(BACKGROUND_COLOR))
         .run();
 }
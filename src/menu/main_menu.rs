@@ -0,0 +1,314 @@
+//! 主菜单：标题和几个按钮，子界面按打开的顺序入栈，返回时回到上一个界面
+
+use bevy::prelude::*;
+
+use super::settings::SettingsState;
+use super::{label_button, text_button, ChangeState, Localized, ThemedText, UiFont};
+use crate::highscore::HighScores;
+use crate::i18n::Localizer;
+use crate::mode::GameMode;
+use crate::save::{set_aside_save, PendingRestore, SaveError, SaveGame, SAVE_FILE};
+use crate::settings::DataDir;
+use crate::theme::Theme;
+use crate::GameState;
+
+pub struct MainMenuPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum MenuScreen {
+    Main,
+    /// 开始游戏前选择模式
+    ModeSelect,
+    /// 某种模式的排行榜
+    HighScores(GameMode),
+    Credits,
+}
+
+/// 打开过的菜单界面，最上面的是当前显示的界面
+#[derive(Resource)]
+pub(crate) struct MenuStack(Vec<MenuScreen>);
+
+impl Default for MenuStack {
+    fn default() -> Self {
+        Self(vec![MenuScreen::Main])
+    }
+}
+
+impl MenuStack {
+    fn top(&self) -> MenuScreen {
+        *self.0.last().unwrap_or(&MenuScreen::Main)
+    }
+
+    fn push(&mut self, screen: MenuScreen) {
+        self.0.push(screen);
+    }
+
+    /// 替换当前界面，返回时不经过被替换的界面
+    fn replace(&mut self, screen: MenuScreen) {
+        match self.0.last_mut() {
+            Some(top) => *top = screen,
+            None => self.0.push(screen),
+        }
+    }
+
+    /// 回到上一个界面，已经在主界面时不变
+    pub(crate) fn pop(&mut self) {
+        if self.0.len() > 1 {
+            self.0.pop();
+        }
+    }
+}
+
+/// 主菜单按钮点击后的动作
+#[derive(Component, Clone, Copy)]
+enum MenuAction {
+    Open(MenuScreen),
+    /// 在同一级的界面之间切换，例如排行榜的模式标签
+    Switch(MenuScreen),
+    Back,
+    Play(GameMode),
+    /// 读取存档继续上次的一局
+    Continue,
+    Quit,
+}
+
+/// 主界面上的提示，例如存档读取失败的原因
+#[derive(Resource, Default)]
+struct MenuNotice(Option<String>);
+
+#[derive(Component)]
+struct Menu;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuStack>()
+            .init_resource::<MenuNotice>()
+            .add_systems(OnEnter(GameState::Menu), reset_menu_stack)
+            .add_systems(OnExit(GameState::Menu), cleanup_menu)
+            .add_systems(
+                Update,
+                (click_menu_action, show_menu_screen)
+                    .chain()
+                    .run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+fn reset_menu_stack(mut stack: ResMut<MenuStack>, mut notice: ResMut<MenuNotice>) {
+    *stack = MenuStack::default();
+    notice.0 = None;
+}
+
+fn click_menu_action(
+    mut commands: Commands,
+    mut stack: ResMut<MenuStack>,
+    mut notice: ResMut<MenuNotice>,
+    dir: Res<DataDir>,
+    localizer: Res<Localizer>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+    interaction_query: Query<(&Interaction, &MenuAction), Changed<Interaction>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *action {
+            MenuAction::Open(screen) => stack.push(screen),
+            MenuAction::Switch(screen) => stack.replace(screen),
+            MenuAction::Back => stack.pop(),
+            MenuAction::Play(chosen) => {
+                *mode = chosen;
+                next_state.set(GameState::Playing);
+            }
+            MenuAction::Continue => {
+                let Some(path) = dir.file(SAVE_FILE) else {
+                    continue;
+                };
+                match SaveGame::load(&path) {
+                    Ok(save) => {
+                        commands.insert_resource(PendingRestore(save));
+                        next_state.set(GameState::Playing);
+                    }
+                    // 读不出来的存档移走，菜单重新生成后不再显示“继续”
+                    Err(err) => {
+                        error!("{}", err);
+                        set_aside_save(&dir);
+                        notice.0 = Some(match err {
+                            SaveError::Version { found, expected } => localizer.get_with(
+                                "save-incompatible",
+                                [("found", found.into()), ("expected", expected.into())],
+                            ),
+                            _ => localizer.get("save-broken"),
+                        });
+                    }
+                }
+            }
+            MenuAction::Quit => {
+                exit.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+// 当前界面变化时重新生成菜单
+fn show_menu_screen(
+    mut commands: Commands,
+    stack: Res<MenuStack>,
+    notice: Res<MenuNotice>,
+    dir: Res<DataDir>,
+    scores: Res<HighScores>,
+    last_mode: Res<GameMode>,
+    theme: Res<Theme>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+    menu: Query<Entity, With<Menu>>,
+) {
+    // 模式名称带参数，不能只替换文字，切换语言时也重新生成
+    if !stack.is_changed() && !notice.is_changed() && !localizer.is_changed() {
+        return;
+    }
+    let has_save = dir.file(SAVE_FILE).is_some_and(|path| path.exists());
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let heading = |parent: &mut ChildBuilder, key: &'static str, font_size: f32| {
+        parent.spawn((
+            TextBundle::from_section(
+                localizer.get(key),
+                TextStyle {
+                    font: font.0.clone(),
+                    font_size,
+                    color: theme.accent.0,
+                },
+            )
+            .with_style(Style {
+                margin: UiRect::bottom(Val::Px(10.0)),
+                ..default()
+            }),
+            ThemedText::Accent,
+            Localized(key),
+        ));
+    };
+    let line = |parent: &mut ChildBuilder, value: String| {
+        parent.spawn((
+            TextBundle::from_section(
+                value,
+                TextStyle {
+                    font: font.0.clone(),
+                    font_size: 20.0,
+                    color: theme.text.0,
+                },
+            ),
+            ThemedText::Text,
+        ));
+    };
+    let button = |parent: &mut ChildBuilder, key: &'static str, bundle: MenuAction| {
+        text_button(parent, &theme, &font, &localizer, key, bundle);
+    };
+    let mode_button = |parent: &mut ChildBuilder, mode: GameMode, bundle: MenuAction| {
+        label_button(parent, &theme, &font, mode.label(&localizer), bundle);
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            Menu,
+        ))
+        .with_children(|parent| match stack.top() {
+            MenuScreen::Main => {
+                heading(parent, "window-title", 64.0);
+                if has_save {
+                    button(parent, "continue", MenuAction::Continue);
+                }
+                button(parent, "play", MenuAction::Open(MenuScreen::ModeSelect));
+                button(
+                    parent,
+                    "high-scores",
+                    MenuAction::Open(MenuScreen::HighScores(*last_mode)),
+                );
+                text_button(
+                    parent,
+                    &theme,
+                    &font,
+                    &localizer,
+                    "settings",
+                    ChangeState(SettingsState::Open),
+                );
+                button(parent, "credits", MenuAction::Open(MenuScreen::Credits));
+                button(parent, "quit", MenuAction::Quit);
+                if let Some(notice) = &notice.0 {
+                    line(parent, notice.clone());
+                }
+            }
+            MenuScreen::ModeSelect => {
+                heading(parent, "choose-mode", 40.0);
+                for mode in GameMode::ALL {
+                    mode_button(parent, mode, MenuAction::Play(mode));
+                }
+                button(parent, "back", MenuAction::Back);
+            }
+            MenuScreen::HighScores(mode) => {
+                heading(parent, "high-scores", 40.0);
+                // 每种模式一个标签，当前模式的标签不显示
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(10.0),
+                            margin: UiRect::bottom(Val::Px(10.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for other in GameMode::ALL.into_iter().filter(|&other| other != mode) {
+                            mode_button(
+                                parent,
+                                other,
+                                MenuAction::Switch(MenuScreen::HighScores(other)),
+                            );
+                        }
+                    });
+                line(parent, mode.label(&localizer));
+                if scores.entries(mode).is_empty() {
+                    line(parent, localizer.get("no-high-scores"));
+                }
+                for (rank, entry) in scores.entries(mode).iter().enumerate() {
+                    line(
+                        parent,
+                        localizer.get_with(
+                            "high-score-line",
+                            [
+                                ("rank", (rank + 1).into()),
+                                ("score", entry.score.into()),
+                                ("streak", entry.best_streak.into()),
+                            ],
+                        ),
+                    );
+                }
+                button(parent, "back", MenuAction::Back);
+            }
+            MenuScreen::Credits => {
+                heading(parent, "credits", 40.0);
+                line(parent, localizer.get("credits-text"));
+                button(parent, "back", MenuAction::Back);
+            }
+        });
+}
+
+fn cleanup_menu(mut commands: Commands, menu: Query<Entity, With<Menu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,404 @@
+use crate::gameover::LastGameOver;
+use crate::i18n::Localizer;
+use crate::score::{Combo, ScoreLog, ScoreSource};
+use crate::theme::Theme;
+use crate::{GameState, PauseState};
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+
+mod main_menu;
+mod pause;
+mod settings;
+
+use main_menu::MenuStack;
+use settings::{capture_rebinding, SettingsState};
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiFont>()
+            .add_plugins((
+                main_menu::MainMenuPlugin,
+                settings::SettingsMenuPlugin,
+                pause::PausePlugin,
+            ))
+            .add_systems(
+                Update,
+                (
+                    button_hover,
+                    change_state::<GameState>,
+                    change_state::<PauseState>,
+                    change_state::<SettingsState>,
+                    (apply_theme, apply_language).chain(),
+                    press_escape.after(capture_rebinding),
+                ),
+            )
+            .add_systems(OnEnter(GameState::GameOver), setup_gameover_menu)
+            .add_systems(OnExit(GameState::GameOver), cleanup_gameover_menu);
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct ButtonColors {
+    normal: Color,
+    hovered: Color,
+}
+
+impl ButtonColors {
+    pub(crate) fn new(theme: &Theme) -> Self {
+        ButtonColors {
+            normal: theme.button.0,
+            hovered: theme.button_hovered.0,
+        }
+    }
+}
+
+/// 颜色跟随主题的文字
+#[derive(Component, Clone, Copy)]
+pub(crate) enum ThemedText {
+    Text,
+    Accent,
+}
+
+impl ThemedText {
+    pub(crate) fn color(self, theme: &Theme) -> Color {
+        match self {
+            Self::Text => theme.text.0,
+            Self::Accent => theme.accent.0,
+        }
+    }
+}
+
+/// 能显示中文的字体，放在 `assets/fonts/` 下，游戏没有自带
+const CJK_FONT: &str = "fonts/NotoSansSC-Regular.ttf";
+
+/// 界面文字使用的字体：有中文字体时用中文字体，否则用 bevy 自带的字体（不能显示中文）
+#[derive(Resource)]
+pub(crate) struct UiFont(pub(crate) Handle<Font>);
+
+impl FromWorld for UiFont {
+    fn from_world(world: &mut World) -> Self {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(CJK_FONT);
+        if !path.exists() {
+            warn!("没有找到 {}，中文将无法显示", path.display());
+            return Self(Handle::default());
+        }
+        Self(world.resource::<AssetServer>().load(CJK_FONT))
+    }
+}
+
+/// 按键名从 `Localizer` 查找的文字，切换语言时自动更新
+#[derive(Component)]
+pub(crate) struct Localized(pub(crate) &'static str);
+
+/// 菜单中的普通按钮：固定大小，文字按键名本地化
+pub(crate) fn text_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    font: &UiFont,
+    localizer: &Localizer,
+    key: &'static str,
+    bundle: impl Bundle,
+) {
+    spawn_button(
+        parent,
+        theme,
+        font,
+        localizer.get(key),
+        bundle,
+        Localized(key),
+    );
+}
+
+/// 文字已经格式化好的按钮，切换语言时需要由调用方重新生成
+pub(crate) fn label_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    font: &UiFont,
+    label: String,
+    bundle: impl Bundle,
+) {
+    spawn_button(parent, theme, font, label, bundle, ());
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    theme: &Theme,
+    font: &UiFont,
+    label: String,
+    bundle: impl Bundle,
+    label_bundle: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(200.0),
+                    height: Val::Px(40.0),
+                    margin: UiRect::top(Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: theme.button.0.into(),
+                ..default()
+            },
+            ButtonColors::new(theme),
+            bundle,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 24.0,
+                        color: theme.text.0,
+                    },
+                ),
+                ThemedText::Text,
+                label_bundle,
+            ));
+        });
+}
+
+// 切换语言时立即更新界面文字
+fn apply_language(localizer: Res<Localizer>, mut texts: Query<(&Localized, &mut Text)>) {
+    if !localizer.is_changed() {
+        return;
+    }
+    for (localized, mut text) in texts.iter_mut() {
+        text.sections[0].value = localizer.get(localized.0);
+    }
+}
+
+// 主题变化时立即给界面换色
+fn apply_theme(
+    theme: Res<Theme>,
+    mut buttons: Query<(&mut ButtonColors, &mut BackgroundColor)>,
+    mut texts: Query<(&ThemedText, &mut Text)>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+    for (mut colors, mut background) in buttons.iter_mut() {
+        *colors = ButtonColors::new(&theme);
+        *background = colors.normal.into();
+    }
+    for (themed, mut text) in texts.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.color = themed.color(&theme);
+        }
+    }
+}
+
+// Esc 依次关闭设置界面、返回上一级菜单、在游戏中切换暂停
+fn press_escape(
+    keys: Res<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameState>>,
+    settings_state: Res<State<SettingsState>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    mut menu_stack: ResMut<MenuStack>,
+    mut next_settings: ResMut<NextState<SettingsState>>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    if *settings_state.get() == SettingsState::Open {
+        next_settings.set(SettingsState::Closed);
+        return;
+    }
+    if *game_state.get() == GameState::Menu {
+        menu_stack.pop();
+        return;
+    }
+    match pause_state.as_deref().map(State::get) {
+        Some(PauseState::Running) => next_pause.set(PauseState::Paused),
+        Some(PauseState::Paused) => next_pause.set(PauseState::Running),
+        None => {}
+    }
+}
+
+/// 点击后切换到的状态
+#[derive(Component)]
+pub(crate) struct ChangeState<S: States>(pub(crate) S);
+
+fn change_state<S: FreelyMutableState>(
+    mut next_state: ResMut<NextState<S>>,
+    interaction_query: Query<(&Interaction, &ChangeState<S>), Changed<Interaction>>,
+) {
+    for (interaction, change_state) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(change_state.0.clone());
+        }
+    }
+}
+
+fn button_hover(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ButtonColors),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, mut color, button_colors) in &mut interaction_query {
+        match *interaction {
+            Interaction::Hovered => *color = button_colors.hovered.into(),
+            Interaction::None => *color = button_colors.normal.into(),
+            Interaction::Pressed => {}
+        }
+    }
+}
+
+#[derive(Component)]
+struct GameOverMenu;
+
+// 游戏结束
+pub fn setup_gameover_menu(
+    mut cmds: Commands,
+    log: Res<ScoreLog>,
+    combo: Res<Combo>,
+    last: Res<LastGameOver>,
+    theme: Res<Theme>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+) {
+    // 显示游戏结束弹窗和重新开始按钮
+    cmds.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: bevy::ui::AlignItems::Center,
+                justify_content: bevy::ui::JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        },
+        GameOverMenu,
+    ))
+    .with_children(|parent| {
+        // 垂直布局，用于显示游戏结束的标题和按钮
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: bevy::ui::FlexDirection::Column,
+                    align_items: bevy::ui::AlignItems::Center,
+                    margin: bevy::ui::UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                let button_colors = ButtonColors::new(&theme);
+
+                // 游戏结束标题
+                parent.spawn((
+                    TextBundle::from_section(
+                        localizer.get("game-over"),
+                        TextStyle {
+                            font: font.0.clone(),
+                            font_size: 60.0,
+                            color: theme.accent.0,
+                        },
+                    ),
+                    ThemedText::Accent,
+                    Localized("game-over"),
+                ));
+                let text_style = TextStyle {
+                    font: font.0.clone(),
+                    font_size: 20.0,
+                    color: theme.text.0,
+                };
+                // 结束的原因
+                if let Some(event) = last.0 {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            localizer.get(&event.cause.key()),
+                            TextStyle {
+                                font_size: 28.0,
+                                ..text_style.clone()
+                            },
+                        ),
+                        ThemedText::Text,
+                    ));
+                }
+                // 得分明细：每种食物吃了几个、存活加了几次分，各得了多少分，以及最长连击
+                for (source, count, points) in log.by_source() {
+                    let color = match source {
+                        ScoreSource::Food(kind) => theme.food(kind),
+                        ScoreSource::Survival => theme.text.0,
+                    };
+                    parent.spawn(TextBundle::from_section(
+                        localizer.get_with(
+                            "breakdown-line",
+                            [
+                                ("food", localizer.get(&source.key()).into()),
+                                ("count", count.into()),
+                                ("points", format!("{:+}", points).into()),
+                            ],
+                        ),
+                        TextStyle {
+                            color,
+                            ..text_style.clone()
+                        },
+                    ));
+                }
+                parent.spawn((
+                    TextBundle::from_section(
+                        localizer.get_with("best-streak", [("streak", combo.best.into())]),
+                        text_style,
+                    ),
+                    ThemedText::Text,
+                ));
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(150.0),
+                                height: Val::Px(65.0),
+                                margin: bevy::ui::UiRect::all(Val::Px(10.0)),
+                                justify_content: bevy::ui::JustifyContent::Center,
+                                align_items: bevy::ui::AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: button_colors.normal.into(),
+                            ..default()
+                        },
+                        button_colors,
+                        ChangeState(GameState::Playing),
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            TextBundle::from_section(
+                                localizer.get("restart"),
+                                TextStyle {
+                                    font: font.0.clone(),
+                                    font_size: 30.0,
+                                    color: theme.text.0,
+                                },
+                            ),
+                            ThemedText::Text,
+                            Localized("restart"),
+                        ));
+                    });
+                text_button(
+                    parent,
+                    &theme,
+                    &font,
+                    &localizer,
+                    "main-menu",
+                    ChangeState(GameState::Menu),
+                );
+            });
+    });
+}
+
+fn cleanup_gameover_menu(mut commands: Commands, menu: Query<Entity, With<GameOverMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,91 @@
+//! 暂停界面：游戏中按 Esc 暂停（见 `press_escape`），可以继续或打开设置
+
+use bevy::prelude::*;
+
+use super::settings::SettingsState;
+use super::{text_button, ChangeState, Localized, ThemedText, UiFont};
+use crate::i18n::Localizer;
+use crate::theme::Theme;
+use crate::{GameState, PauseState};
+
+pub struct PausePlugin;
+
+#[derive(Component)]
+struct PauseMenu;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(PauseState::Paused), setup_pause_menu)
+            .add_systems(OnExit(PauseState::Paused), cleanup_pause_menu);
+    }
+}
+
+fn setup_pause_menu(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                // 半透明，还能看到下面的棋盘
+                background_color: theme.background.0.with_alpha(0.7).into(),
+                ..default()
+            },
+            PauseMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    localizer.get("paused"),
+                    TextStyle {
+                        font: font.0.clone(),
+                        font_size: 50.0,
+                        color: theme.accent.0,
+                    },
+                ),
+                ThemedText::Accent,
+                Localized("paused"),
+            ));
+            text_button(
+                parent,
+                &theme,
+                &font,
+                &localizer,
+                "resume",
+                ChangeState(PauseState::Running),
+            );
+            text_button(
+                parent,
+                &theme,
+                &font,
+                &localizer,
+                "settings",
+                ChangeState(SettingsState::Open),
+            );
+            // 暂停时已经自动保存，回到主菜单后可以继续
+            text_button(
+                parent,
+                &theme,
+                &font,
+                &localizer,
+                "main-menu",
+                ChangeState(GameState::Menu),
+            );
+        });
+}
+
+fn cleanup_pause_menu(mut commands: Commands, menu: Query<Entity, With<PauseMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,337 @@
+//! 设置界面：主菜单和暂停界面都可以打开，修改立即生效并保存
+
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use fluent_bundle::FluentValue;
+
+use super::{text_button, ButtonColors, ChangeState, ThemedText, UiFont};
+use crate::actions::control::GameControl;
+use crate::i18n::Localizer;
+use crate::settings::Settings;
+use crate::theme::{Theme, ThemeRegistry};
+
+pub struct SettingsMenuPlugin;
+
+/// 设置界面是否打开，打开时盖在当前界面上面
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) enum SettingsState {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// 正在等待新按键的方向
+#[derive(Resource, Default)]
+pub(crate) struct Rebinding(Option<GameControl>);
+
+/// 设置界面中的一行，点击时切换到下一个值
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SettingRow {
+    Arena,
+    Speed,
+    Walls,
+    Collision,
+    Portals,
+    Enemies,
+    Theme,
+    Volume,
+    Language,
+    Effects,
+    Movement,
+    Fps,
+    Bind(GameControl),
+}
+
+/// 音量可选的几档
+const VOLUME_STEPS: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+impl SettingRow {
+    const GENERAL: [SettingRow; 12] = [
+        Self::Arena,
+        Self::Speed,
+        Self::Walls,
+        Self::Collision,
+        Self::Portals,
+        Self::Enemies,
+        Self::Theme,
+        Self::Volume,
+        Self::Language,
+        Self::Effects,
+        Self::Movement,
+        Self::Fps,
+    ];
+
+    fn label(self, settings: &Settings, localizer: &Localizer, rebinding: &Rebinding) -> String {
+        let on_off = |on: bool| localizer.get(if on { "value-on" } else { "value-off" });
+        let (name, value) = match self {
+            Self::Arena => {
+                let arena = settings.arena.arena();
+                (
+                    localizer.get("setting-arena"),
+                    format!("{}x{}", arena.width, arena.height),
+                )
+            }
+            Self::Speed => (
+                localizer.get("setting-speed"),
+                localizer.get(&format!("speed-{:?}", settings.speed)),
+            ),
+            Self::Walls => (
+                localizer.get("setting-walls"),
+                localizer.get(&format!("walls-{:?}", settings.wall_mode)),
+            ),
+            Self::Collision => (
+                localizer.get("setting-collision"),
+                localizer.get(&format!("collision-{:?}", settings.collision)),
+            ),
+            Self::Portals => (localizer.get("setting-portals"), on_off(settings.portals)),
+            Self::Enemies => (localizer.get("setting-enemies"), on_off(settings.enemies)),
+            Self::Theme => (localizer.get("setting-theme"), settings.theme.clone()),
+            Self::Volume => (
+                localizer.get("setting-volume"),
+                format!("{:.0}%", settings.volume * 100.0),
+            ),
+            Self::Language => (
+                localizer.get("setting-language"),
+                settings.language.native_name().to_string(),
+            ),
+            Self::Effects => (localizer.get("setting-effects"), on_off(settings.effects)),
+            Self::Movement => (
+                localizer.get("setting-movement"),
+                on_off(settings.smooth_movement),
+            ),
+            Self::Fps => (localizer.get("setting-fps"), on_off(settings.show_fps)),
+            Self::Bind(control) if rebinding.0 == Some(control) => (
+                localizer.get(&format!("control-{:?}", control)),
+                localizer.get("press-key"),
+            ),
+            Self::Bind(control) => (
+                localizer.get(&format!("control-{:?}", control)),
+                settings
+                    .key_bindings
+                    .keys(control)
+                    .iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            ),
+        };
+        localizer.get_with(
+            "setting-row",
+            [
+                ("name", FluentValue::from(name)),
+                ("value", FluentValue::from(value)),
+            ],
+        )
+    }
+}
+
+#[derive(Component)]
+struct SettingsMenu;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<SettingsState>()
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(SettingsState::Open), setup_settings_menu)
+            .add_systems(OnExit(SettingsState::Open), cleanup_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    capture_rebinding,
+                    click_setting_row,
+                    update_setting_labels.after(crate::render::apply_settings),
+                )
+                    .chain()
+                    .run_if(in_state(SettingsState::Open)),
+            );
+    }
+}
+
+fn setup_settings_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+    theme: Res<Theme>,
+    localizer: Res<Localizer>,
+    font: Res<UiFont>,
+) {
+    let column = || NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            margin: UiRect::horizontal(Val::Px(5.0)),
+            ..default()
+        },
+        ..default()
+    };
+    let row = |parent: &mut ChildBuilder, row: SettingRow| {
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(230.0),
+                        height: Val::Px(32.0),
+                        margin: UiRect::top(Val::Px(6.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: theme.button.0.into(),
+                    ..default()
+                },
+                ButtonColors::new(&theme),
+                row,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(
+                        row.label(&settings, &localizer, &rebinding),
+                        TextStyle {
+                            font: font.0.clone(),
+                            font_size: 18.0,
+                            color: theme.text.0,
+                        },
+                    ),
+                    ThemedText::Text,
+                ));
+            });
+    };
+
+    // 盖住下面的界面，并挡住对下面按钮的点击
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: theme.background.0.into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            SettingsMenu,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::FlexStart,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(column()).with_children(|parent| {
+                        // 没有编译音频时音量不起作用，不显示
+                        for setting in SettingRow::GENERAL {
+                            if setting != SettingRow::Volume || cfg!(feature = "audio") {
+                                row(parent, setting);
+                            }
+                        }
+                    });
+                    parent.spawn(column()).with_children(|parent| {
+                        for control in GameControl::ALL {
+                            row(parent, SettingRow::Bind(control));
+                        }
+                    });
+                });
+            text_button(
+                parent,
+                &theme,
+                &font,
+                &localizer,
+                "back",
+                ChangeState(SettingsState::Closed),
+            );
+        });
+}
+
+// 点击一行：选项换成下一个值，按键一行开始等待新的按键
+fn click_setting_row(
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    registry: Res<ThemeRegistry>,
+    interaction_query: Query<(&Interaction, &SettingRow), Changed<Interaction>>,
+) {
+    for (interaction, row) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *row {
+            SettingRow::Arena => settings.arena = settings.arena.next(),
+            SettingRow::Speed => settings.speed = settings.speed.next(),
+            SettingRow::Walls => settings.wall_mode = settings.wall_mode.next(),
+            SettingRow::Collision => settings.collision = settings.collision.next(),
+            SettingRow::Portals => settings.portals = !settings.portals,
+            SettingRow::Enemies => settings.enemies = !settings.enemies,
+            SettingRow::Theme => settings.theme = registry.next_after(&settings.theme).name.clone(),
+            SettingRow::Volume => {
+                let index = VOLUME_STEPS
+                    .iter()
+                    .position(|&step| step >= settings.volume)
+                    .unwrap_or(0);
+                settings.volume = VOLUME_STEPS[(index + 1) % VOLUME_STEPS.len()];
+            }
+            SettingRow::Language => settings.language = settings.language.next(),
+            SettingRow::Effects => settings.effects = !settings.effects,
+            SettingRow::Movement => settings.smooth_movement = !settings.smooth_movement,
+            SettingRow::Fps => settings.show_fps = !settings.show_fps,
+            SettingRow::Bind(control) => rebinding.0 = Some(control),
+        }
+    }
+}
+
+// 等待新按键时，下一个按下的键绑定到这个方向，Esc 取消
+pub(crate) fn capture_rebinding(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(control) = rebinding.0 else {
+        return;
+    };
+    let Some(&key) = keys.get_just_pressed().next() else {
+        return;
+    };
+    // 这个键只用来绑定，不再交给其他系统（例如 Esc 不会关闭设置界面）
+    keys.clear_just_pressed(key);
+    rebinding.0 = None;
+    if key != KeyCode::Escape {
+        settings.key_bindings.bind(control, key);
+    }
+}
+
+fn update_setting_labels(
+    settings: Res<Settings>,
+    localizer: Res<Localizer>,
+    rebinding: Res<Rebinding>,
+    rows: Query<(&SettingRow, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !localizer.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (row, children) in rows.iter() {
+        let mut iter = texts.iter_many_mut(children);
+        while let Some(mut text) = iter.fetch_next() {
+            text.sections[0].value = row.label(&settings, &localizer, &rebinding);
+        }
+    }
+}
+
+fn cleanup_settings_menu(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    menu: Query<Entity, With<SettingsMenu>>,
+) {
+    rebinding.0 = None;
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
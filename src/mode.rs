@@ -0,0 +1,189 @@
+//! 游戏模式：每种模式有自己的结束条件和计分方式
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::Position;
+use crate::bounds::Bounds;
+use crate::gameover::{GameOverCause, GameOverEvent};
+use crate::i18n::Localizer;
+use crate::obstacle::{random_obstacle_cell, spawn_obstacle_at};
+use crate::score::{AddScoreEvent, ScoreSource};
+use crate::snake::SnakeHead;
+use crate::tick::{GameSet, GameTick};
+use crate::{GameRng, GameState};
+
+pub struct ModePlugin;
+
+/// 这一局的玩法，在主菜单开始游戏时选择
+#[derive(
+    Resource,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+pub enum GameMode {
+    /// 撞墙或撞到自己结束
+    #[default]
+    Classic,
+    /// 限时（秒），时间到时结束，比谁的分数高
+    TimeAttack(u32),
+    /// 障碍物越来越多，场地越来越小，活得越久分数越高
+    Survival,
+}
+
+/// 生存模式每隔这么多秒出现一个障碍物
+pub const SURVIVAL_OBSTACLE_SECS: f32 = 10.0;
+
+/// 生存模式每存活这么多秒加一分
+pub const SURVIVAL_POINT_SECS: f32 = 5.0;
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        Self::Classic,
+        Self::TimeAttack(60),
+        Self::TimeAttack(120),
+        Self::Survival,
+    ];
+
+    /// 菜单和排行榜上显示的名称
+    pub fn label(self, localizer: &Localizer) -> String {
+        match self {
+            Self::Classic => localizer.get("mode-Classic"),
+            Self::TimeAttack(secs) => {
+                localizer.get_with("mode-TimeAttack", [("secs", secs.into())])
+            }
+            Self::Survival => localizer.get("mode-Survival"),
+        }
+    }
+
+    pub fn time_limit(self) -> Option<Duration> {
+        match self {
+            Self::TimeAttack(secs) => Some(Duration::from_secs(secs.into())),
+            _ => None,
+        }
+    }
+
+    /// 生成障碍物的间隔秒数，`None` 表示不生成
+    pub fn obstacle_interval(self) -> Option<f32> {
+        match self {
+            Self::Survival => Some(SURVIVAL_OBSTACLE_SECS),
+            _ => None,
+        }
+    }
+
+    /// 活动范围是否按 `ShrinkSchedule` 缩小
+    pub fn shrinks(self) -> bool {
+        self == Self::Survival
+    }
+
+    /// 因为存活时间而加分的间隔秒数，`None` 表示只有食物得分
+    pub fn survival_point_interval(self) -> Option<f32> {
+        match self {
+            Self::Survival => Some(SURVIVAL_POINT_SECS),
+            _ => None,
+        }
+    }
+}
+
+/// 这一局已经进行的时间，暂停时不计
+#[derive(Resource, Clone, Copy, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ModeClock {
+    pub elapsed: Duration,
+}
+
+impl ModeClock {
+    /// 限时模式的剩余时间
+    pub fn remaining(&self, mode: GameMode) -> Option<Duration> {
+        mode.time_limit()
+            .map(|limit| limit.saturating_sub(self.elapsed))
+    }
+}
+
+// 从 before 到 after 之间经过了几个 every 秒的整数倍
+fn crossings(before: Duration, after: Duration, every: f32) -> u32 {
+    let count = |time: Duration| (time.as_secs_f32() / every) as u32;
+    count(after) - count(before)
+}
+
+impl Plugin for ModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ModeClock>()
+            .add_systems(OnEnter(GameState::Playing), reset_mode_clock)
+            .add_systems(OnEnter(GameState::Menu), reset_mode_clock)
+            .add_systems(FixedUpdate, run_mode_rules.in_set(GameSet::Collide));
+    }
+}
+
+pub fn reset_mode_clock(mut clock: ResMut<ModeClock>) {
+    *clock = ModeClock::default();
+}
+
+// 推进计时，检查限时模式是否结束，生存模式加分并生成障碍物
+pub fn run_mode_rules(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mode: Res<GameMode>,
+    mut clock: ResMut<ModeClock>,
+    mut score_writer: EventWriter<AddScoreEvent>,
+    mut rng: ResMut<GameRng>,
+    bounds: Res<Bounds>,
+    tick: Res<GameTick>,
+    mut game_over: EventWriter<GameOverEvent>,
+    heads: Query<(Entity, &Position), With<SnakeHead>>,
+    occupied: Query<&Position>,
+) {
+    let before = clock.elapsed;
+    clock.elapsed += time.delta();
+    let after = clock.elapsed;
+
+    if clock.remaining(*mode) == Some(Duration::ZERO) {
+        if let Ok((_, &head)) = heads.get_single() {
+            game_over.send(GameOverEvent {
+                cause: GameOverCause::Timeout,
+                tick: tick.0,
+                position: head,
+            });
+        }
+        return;
+    }
+    let Ok((snake, &head)) = heads.get_single() else {
+        return;
+    };
+    // 存活加分和吃食物一样记入得分明细
+    if let Some(every) = mode.survival_point_interval() {
+        let points = crossings(before, after, every) as i32;
+        if points > 0 {
+            score_writer.send(AddScoreEvent {
+                snake,
+                source: ScoreSource::Survival,
+                position: head,
+                points,
+                multiplier: 1,
+                streak: 0,
+            });
+        }
+    }
+    let Some(every) = mode.obstacle_interval() else {
+        return;
+    };
+    let mut occupied = occupied.iter().copied().collect::<HashSet<_>>();
+    for _ in 0..crossings(before, after, every) {
+        let Some(pos) = random_obstacle_cell(&mut rng, &bounds, &occupied, head) else {
+            return;
+        };
+        spawn_obstacle_at(&mut cmds, pos);
+        occupied.insert(pos);
+    }
+}
@@ -0,0 +1,50 @@
+//! 障碍物：占一个格子，蛇头撞上时游戏结束（在 `move_snake` 中判断）
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::actions::{Position, Size};
+use crate::bounds::Bounds;
+use crate::food::random_free_cell;
+use crate::{GameRng, GameState};
+
+pub struct ObstaclePlugin;
+
+#[derive(Component)]
+pub struct Obstacle;
+
+/// 新的障碍物离蛇头至少这么远（曼哈顿距离），不会突然出现在蛇的面前
+pub const OBSTACLE_CLEARANCE: i32 = 3;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOver), cleanup_obstacles)
+            .add_systems(OnEnter(GameState::Menu), cleanup_obstacles);
+    }
+}
+
+pub fn spawn_obstacle_at(cmds: &mut Commands, pos: Position) -> Entity {
+    cmds.spawn((Obstacle, pos, Size::square(1.0))).id()
+}
+
+/// 随机选一个空格子放障碍物，避开蛇头附近
+pub fn random_obstacle_cell(
+    rng: &mut GameRng,
+    bounds: &Bounds,
+    occupied: &HashSet<Position>,
+    head: Position,
+) -> Option<Position> {
+    let mut blocked = occupied.clone();
+    blocked.extend(
+        bounds
+            .cells()
+            .filter(|pos| (pos.x - head.x).abs() + (pos.y - head.y).abs() <= OBSTACLE_CLEARANCE),
+    );
+    random_free_cell(rng, bounds, &blocked)
+}
+
+fn cleanup_obstacles(mut cmds: Commands, obstacles: Query<Entity, With<Obstacle>>) {
+    for entity in obstacles.iter() {
+        cmds.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,91 @@
+//! 传送门：成对出现，蛇头走进一个后从另一个出来，方向不变，蛇身一节一节跟着穿过
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::actions::{Position, Size};
+use crate::bounds::Bounds;
+use crate::obstacle::random_obstacle_cell;
+use crate::settings::apply_arena;
+use crate::snake::{spawn_snake, SnakeHead};
+use crate::{Arena, GameRng, GameState};
+
+pub struct PortalPlugin;
+
+/// 传送门所在的格子，`partner` 是成对的另一个传送门
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Portal {
+    pub partner: Position,
+}
+
+/// 每局开始时放置的传送门：`pairs` 是固定位置的几对，另外随机放 `random_pairs` 对
+#[derive(Resource, Clone, Default, PartialEq, Debug)]
+pub struct PortalConfig {
+    pub pairs: Vec<(Position, Position)>,
+    pub random_pairs: u32,
+}
+
+/// 在设置中打开传送门时每局随机放置的对数
+pub const RANDOM_PORTAL_PAIRS: u32 = 1;
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PortalConfig>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                spawn_portals.after(spawn_snake).after(apply_arena),
+            )
+            .add_systems(OnEnter(GameState::GameOver), cleanup_portals)
+            .add_systems(OnEnter(GameState::Menu), cleanup_portals);
+    }
+}
+
+/// 生成一对传送门
+pub fn spawn_portal_pair(cmds: &mut Commands, a: Position, b: Position) {
+    cmds.spawn((Portal { partner: b }, a, Size::square(1.0)));
+    cmds.spawn((Portal { partner: a }, b, Size::square(1.0)));
+}
+
+// 先放固定位置的传送门，再随机放，随机位置取自 GameRng，固定种子时每局相同；
+// 开局时还没有缩圈，活动范围是整个竞技场
+pub fn spawn_portals(
+    mut cmds: Commands,
+    config: Res<PortalConfig>,
+    arena: Res<Arena>,
+    mut rng: ResMut<GameRng>,
+    heads: Query<&Position, With<SnakeHead>>,
+    occupied: Query<&Position>,
+) {
+    let bounds = Bounds::full(&arena);
+    let mut occupied = occupied.iter().copied().collect::<HashSet<_>>();
+    for &(a, b) in &config.pairs {
+        let usable = |pos: Position| bounds.contains(pos) && !occupied.contains(&pos);
+        if a == b || !usable(a) || !usable(b) {
+            warn!("传送门 {:?} 和 {:?} 的位置不可用，跳过", a, b);
+            continue;
+        }
+        spawn_portal_pair(&mut cmds, a, b);
+        occupied.extend([a, b]);
+    }
+    let Ok(&head) = heads.get_single() else {
+        return;
+    };
+    // 和障碍物一样不放在蛇头附近
+    for _ in 0..config.random_pairs {
+        let Some(a) = random_obstacle_cell(&mut rng, &bounds, &occupied, head) else {
+            return;
+        };
+        occupied.insert(a);
+        let Some(b) = random_obstacle_cell(&mut rng, &bounds, &occupied, head) else {
+            return;
+        };
+        occupied.insert(b);
+        spawn_portal_pair(&mut cmds, a, b);
+    }
+}
+
+fn cleanup_portals(mut cmds: Commands, portals: Query<Entity, With<Portal>>) {
+    for entity in portals.iter() {
+        cmds.entity(entity).despawn();
+    }
+}
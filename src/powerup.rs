@@ -0,0 +1,352 @@
+use std::fmt;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::Deserialize;
+
+use crate::actions::{Position, Size};
+use crate::food::{random_free_cell, Food};
+use crate::snake::{snake_eating, GrowthEvent, GrowthSource, SnakeBlock, SnakeHead};
+use crate::tick::GameSet;
+use crate::bounds::Bounds;
+use crate::enemy::Enemy;
+use crate::obstacle::Obstacle;
+use crate::portal::Portal;
+use crate::{GameRng, GameState};
+
+pub struct PowerUpPlugin;
+
+/// 道具的效果
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Effect {
+    /// 蛇的移动速度乘以这个系数
+    SlowMotion(f32),
+    /// 可以穿过自己的身体
+    Ghost,
+    /// 蛇每移动一格，这个距离内的食物向蛇头靠近一格
+    Magnet(i32),
+    /// 拾取时立即去掉尾部的几节，不受持续时间影响
+    Shrink(usize),
+    /// 得分翻倍
+    DoubleScore,
+}
+
+/// 效果还在持续时再次拾取同一种道具的规则
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Stacking {
+    /// 重新开始计时
+    Refresh,
+    /// 剩余时间加上一次完整的时长
+    Extend,
+    /// 效果叠加一层并重新计时，最多叠加到这个层数
+    Stack(u32),
+}
+
+/// 一种道具的定义，见 `assets/powerups.ron`
+#[derive(Deserialize, Clone, Debug)]
+pub struct PowerUpDef {
+    pub name: String,
+    pub effect: Effect,
+    pub duration: f32,
+    pub stacking: Stacking,
+    pub weight: u32,
+    pub color: (f32, f32, f32),
+}
+
+impl PowerUpDef {
+    pub fn color(&self) -> Color {
+        let (r, g, b) = self.color;
+        Color::srgb(r, g, b)
+    }
+
+    /// 拾取时蛇长度的变化
+    pub fn growth(&self) -> i32 {
+        match self.effect {
+            Effect::Shrink(count) => -(count as i32),
+            _ => 0,
+        }
+    }
+}
+
+/// 所有可以生成的道具，为空时不生成道具
+#[derive(Resource, Default)]
+pub struct PowerUpRegistry(pub Vec<PowerUpDef>);
+
+#[derive(Debug)]
+pub enum PowerUpError {
+    Parse(ron::error::SpannedError),
+    /// 减速系数必须大于 0，否则蛇的移动间隔会变成无穷大或负数
+    InvalidSlowMotion { name: String, factor: f32 },
+}
+
+impl fmt::Display for PowerUpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "道具定义格式错误：{}", err),
+            Self::InvalidSlowMotion { name, factor } => {
+                write!(f, "道具 {} 的减速系数必须大于 0，实际为 {}", name, factor)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PowerUpError {}
+
+impl PowerUpRegistry {
+    pub fn from_ron(source: &str) -> Result<Self, PowerUpError> {
+        let defs: Vec<PowerUpDef> = ron::from_str(source).map_err(PowerUpError::Parse)?;
+        for def in &defs {
+            if let Effect::SlowMotion(factor) = def.effect {
+                if factor.is_nan() || factor <= 0.0 {
+                    return Err(PowerUpError::InvalidSlowMotion { name: def.name.clone(), factor });
+                }
+            }
+        }
+        Ok(Self(defs))
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|def| def.name == name)
+    }
+}
+
+/// 场上的道具，值为它在 `PowerUpRegistry` 中的下标
+#[derive(Component)]
+pub struct PowerUp(pub usize);
+
+#[derive(Resource)]
+pub struct PowerUpSpawnTimer(pub Timer);
+
+impl PowerUpSpawnTimer {
+    pub fn new(d: f32) -> Self {
+        Self(Timer::from_seconds(d, TimerMode::Repeating))
+    }
+}
+
+/// 正在生效的道具
+pub struct ActiveEffect {
+    pub def: usize,
+    pub stacks: u32,
+    pub timer: Timer,
+}
+
+#[derive(Resource, Default)]
+pub struct ActiveEffects(pub Vec<ActiveEffect>);
+
+impl ActiveEffects {
+    /// 按道具的叠加规则激活效果
+    pub fn activate(&mut self, index: usize, def: &PowerUpDef) {
+        let duration = Duration::from_secs_f32(def.duration);
+        let Some(active) = self.0.iter_mut().find(|active| active.def == index) else {
+            self.0.push(ActiveEffect {
+                def: index,
+                stacks: 1,
+                timer: Timer::new(duration, TimerMode::Once),
+            });
+            return;
+        };
+        match def.stacking {
+            Stacking::Refresh => active.timer.reset(),
+            Stacking::Extend => {
+                active.timer = Timer::new(active.timer.remaining() + duration, TimerMode::Once);
+            }
+            Stacking::Stack(max) => {
+                active.stacks = (active.stacks + 1).min(max);
+                active.timer.reset();
+            }
+        }
+    }
+}
+
+/// 所有生效道具合起来对规则的影响
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct EffectModifiers {
+    pub speed_scale: f32,
+    pub ghost: bool,
+    pub magnet_radius: i32,
+    pub score_multiplier: u32,
+}
+
+impl Default for EffectModifiers {
+    fn default() -> Self {
+        Self {
+            speed_scale: 1.0,
+            ghost: false,
+            magnet_radius: 0,
+            score_multiplier: 1,
+        }
+    }
+}
+
+impl EffectModifiers {
+    fn from_active(effects: &ActiveEffects, registry: &PowerUpRegistry) -> Self {
+        let mut modifiers = Self::default();
+        for active in &effects.0 {
+            match registry.0[active.def].effect {
+                Effect::SlowMotion(scale) => {
+                    modifiers.speed_scale *= scale.powi(active.stacks as i32)
+                }
+                Effect::Ghost => modifiers.ghost = true,
+                Effect::Magnet(radius) => {
+                    modifiers.magnet_radius = modifiers.magnet_radius.max(radius)
+                }
+                Effect::DoubleScore => modifiers.score_multiplier *= 2u32.pow(active.stacks),
+                Effect::Shrink(_) => {}
+            }
+        }
+        modifiers
+    }
+}
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        let registry = PowerUpRegistry::from_ron(include_str!("../assets/powerups.ron"))
+            .expect("assets/powerups.ron 格式错误");
+        app.insert_resource(registry)
+            .insert_resource(PowerUpSpawnTimer::new(8.0))
+            .init_resource::<ActiveEffects>()
+            .init_resource::<EffectModifiers>()
+            .add_systems(OnEnter(GameState::GameOver), cleanup_power_ups)
+            .add_systems(OnEnter(GameState::Menu), cleanup_power_ups)
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_effects
+                        .before(GameSet::Move)
+                        .run_if(in_state(GameState::Playing)),
+                    (collect_power_up, magnet_food.before(snake_eating)).in_set(GameSet::Eat),
+                    spawn_power_up.in_set(GameSet::Spawn),
+                ),
+            );
+    }
+}
+
+// 场上没有道具时，按权重随机生成一个
+pub fn spawn_power_up(
+    mut cmds: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<PowerUpSpawnTimer>,
+    mut rng: ResMut<GameRng>,
+    registry: Res<PowerUpRegistry>,
+    bounds: Res<Bounds>,
+    power_ups: Query<(), With<PowerUp>>,
+    occupied: Query<&Position>,
+) {
+    if !timer.0.tick(time.delta()).finished() || !power_ups.is_empty() {
+        return;
+    }
+    let Ok(weights) = WeightedIndex::new(registry.0.iter().map(|def| def.weight)) else {
+        return;
+    };
+    let occupied = occupied.iter().copied().collect::<HashSet<_>>();
+    let Some(pos) = random_free_cell(&mut rng, &bounds, &occupied) else {
+        return;
+    };
+    let index = weights.sample(&mut rng.0);
+    cmds.spawn(PowerUp(index))
+        .insert(pos)
+        .insert(Size::square(0.8));
+}
+
+pub fn tick_effects(
+    time: Res<Time>,
+    registry: Res<PowerUpRegistry>,
+    mut effects: ResMut<ActiveEffects>,
+    mut modifiers: ResMut<EffectModifiers>,
+) {
+    for active in effects.0.iter_mut() {
+        active.timer.tick(time.delta());
+    }
+    effects.0.retain(|active| !active.timer.finished());
+    modifiers.set_if_neq(EffectModifiers::from_active(&effects, &registry));
+}
+
+pub fn collect_power_up(
+    mut cmds: Commands,
+    registry: Res<PowerUpRegistry>,
+    mut effects: ResMut<ActiveEffects>,
+    mut growth_writer: EventWriter<GrowthEvent>,
+    power_ups: Query<(Entity, &PowerUp, &Position)>,
+    heads: Query<(Entity, &Position), With<SnakeHead>>,
+) {
+    for (snake, head_pos) in heads.iter() {
+        for (entity, power_up, pos) in power_ups.iter() {
+            if pos != head_pos {
+                continue;
+            }
+            cmds.entity(entity).despawn();
+            let def = &registry.0[power_up.0];
+            match def.effect {
+                Effect::Shrink(_) => {
+                    growth_writer.send(GrowthEvent {
+                        snake,
+                        amount: def.growth(),
+                        source: GrowthSource::PowerUp(power_up.0),
+                    });
+                }
+                _ => effects.activate(power_up.0, def),
+            }
+        }
+    }
+}
+
+// 磁铁：蛇每移动一格，范围内的食物沿距离较远的方向向蛇头靠近一格
+// 只走进范围内空着的格子，不盖住蛇、障碍物、传送门、敌人和别的食物，走不了就留在原地
+pub fn magnet_food(
+    modifiers: Res<EffectModifiers>,
+    bounds: Res<Bounds>,
+    heads: Query<&Position, With<SnakeHead>>,
+    blockers: Query<
+        &Position,
+        (
+            Or<(With<SnakeBlock>, With<Obstacle>, With<Portal>, With<Enemy>)>,
+            Without<Food>,
+        ),
+    >,
+    mut food: Query<&mut Position, (With<Food>, Without<SnakeHead>)>,
+) {
+    if modifiers.magnet_radius <= 0 {
+        return;
+    }
+    let Ok(head) = heads.get_single() else {
+        return;
+    };
+    let mut occupied = blockers
+        .iter()
+        .chain(food.iter())
+        .copied()
+        .collect::<HashSet<_>>();
+    for mut pos in food.iter_mut() {
+        let (dx, dy) = (head.x - pos.x, head.y - pos.y);
+        if dx.abs() + dy.abs() > modifiers.magnet_radius {
+            continue;
+        }
+        let mut next = *pos;
+        if dx.abs() >= dy.abs() {
+            next.x += dx.signum();
+        } else {
+            next.y += dy.signum();
+        }
+        if !bounds.contains(next) || occupied.contains(&next) {
+            continue;
+        }
+        occupied.remove(&*pos);
+        occupied.insert(next);
+        *pos = next;
+    }
+}
+
+fn cleanup_power_ups(
+    mut cmds: Commands,
+    mut effects: ResMut<ActiveEffects>,
+    mut modifiers: ResMut<EffectModifiers>,
+    power_ups: Query<Entity, With<PowerUp>>,
+) {
+    for entity in power_ups.iter() {
+        cmds.entity(entity).despawn();
+    }
+    effects.0.clear();
+    *modifiers = EffectModifiers::default();
+}
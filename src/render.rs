@@ -0,0 +1,430 @@
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::actions::{Position, Size};
+use crate::bounds::Bounds;
+use crate::effects::EffectsSettings;
+use crate::enemy::Enemy;
+use crate::food::{Food, FoodLifetime};
+use crate::i18n::Localizer;
+use crate::layout::{Layout, HUD_HEIGHT};
+use crate::obstacle::Obstacle;
+use crate::portal::Portal;
+use crate::powerup::{PowerUp, PowerUpRegistry};
+use crate::settings::Settings;
+use crate::snake::{SnakeBlock, SnakeHead};
+use crate::theme::{Theme, ThemeRegistry};
+use crate::{Arena, GameState};
+
+/// 把格子坐标映射到窗口中的精灵
+pub struct RenderPlugin;
+
+/// 蛇的显示方式，只影响画面，规则仍然按格子走
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MovementStyle {
+    /// 每次移动直接跳到下一格
+    #[default]
+    Snap,
+    /// 在两次移动之间平滑过渡，拐弯处走圆角
+    Smooth,
+}
+
+/// 竞技场的底色，窗口比例和竞技场不同时两侧露出的是清屏颜色
+#[derive(Component)]
+struct ArenaBackground;
+
+/// 活动范围外的危险区，每边一个矩形
+#[derive(Component, Clone, Copy)]
+enum DangerZone {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+impl DangerZone {
+    const ALL: [DangerZone; 4] = [Self::Left, Self::Right, Self::Bottom, Self::Top];
+
+    /// 覆盖的格子范围：左下角的格子和右上角之外的格子，范围为空时两者的某一维相同
+    fn cells(self, bounds: &Bounds, arena: &Arena) -> (Vec2, Vec2) {
+        let (w, h) = (arena.width as f32, arena.height as f32);
+        let min = Vec2::new(bounds.min.x as f32, bounds.min.y as f32);
+        let max = Vec2::new(bounds.max.x as f32 + 1.0, bounds.max.y as f32 + 1.0);
+        match self {
+            Self::Left => (Vec2::ZERO, Vec2::new(min.x, h)),
+            Self::Right => (Vec2::new(max.x, 0.0), Vec2::new(w, h)),
+            Self::Bottom => (Vec2::new(min.x, 0.0), Vec2::new(max.x, min.y)),
+            Self::Top => (Vec2::new(min.x, max.y), Vec2::new(max.x, h)),
+        }
+    }
+}
+
+/// 蛇的一节最近两次移动，用于平滑移动时插值
+#[derive(Component)]
+pub struct Tween {
+    from: Position,
+    to: Position,
+    /// 上一次移动的方向
+    d_in: Vec2,
+    /// 这一次移动的方向；穿墙或穿过传送门时 `to` 不在 `from` 旁边，但也只走了一格
+    d_out: Vec2,
+}
+
+fn cell(pos: Position) -> Vec2 {
+    Vec2::new(pos.x as f32, pos.y as f32)
+}
+
+impl Tween {
+    pub fn at(pos: Position) -> Self {
+        Self {
+            from: pos,
+            to: pos,
+            d_in: Vec2::ZERO,
+            d_out: Vec2::ZERO,
+        }
+    }
+
+    // 这一拍没有移动，停在原地，不再重放上一次移动
+    fn settle(&mut self) {
+        *self = Self::at(self.to);
+    }
+
+    // 按这次移动的进度 t 计算所在的格子坐标（可以是小数）
+    fn sample(&self, t: f32) -> Vec2 {
+        let (from, to) = (cell(self.from), cell(self.to));
+        let (d_in, d_out) = (self.d_in, self.d_out);
+        // 没有移动或不知道怎么过去的（例如读档）直接停在目标格子
+        if d_out == Vec2::ZERO {
+            return to;
+        }
+        // 穿墙或穿过传送门：前半程走进入口，后半程从出口的另一侧出来
+        if to - from != d_out {
+            return if t < 0.5 {
+                from + d_out * t
+            } else {
+                to - d_out * (1.0 - t)
+            };
+        }
+        if d_in == d_out || d_in == Vec2::ZERO {
+            return from.lerp(to, t);
+        }
+        // 拐弯：以沿原方向前进半格的点为控制点的二次贝塞尔曲线
+        let control = from + d_in * 0.5;
+        from.lerp(control, t).lerp(control.lerp(to, t), t)
+    }
+}
+
+impl Plugin for RenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MovementStyle>()
+            .init_resource::<Layout>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(PreUpdate, (apply_settings, update_layout))
+            .add_systems(PostUpdate, attach_sprites)
+            // 每个节拍之后都记录一次，一帧模拟多个节拍时也不会漏掉中间的格子
+            .add_systems(
+                FixedPostUpdate,
+                track_moves.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_movement_style,
+                    apply_theme,
+                    update_window_title,
+                    size_arena_background,
+                    size_danger_zone,
+                    size_scaling.run_if(in_state(GameState::Playing)),
+                    position_translation.run_if(in_state(GameState::Playing)),
+                    blink_food.run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_xyz(0.0, 0.0, -1.0),
+            ..default()
+        },
+        ArenaBackground,
+    ));
+    for zone in DangerZone::ALL {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(0.0, 0.0, -0.5),
+                ..default()
+            },
+            zone,
+        ));
+    }
+}
+
+// 窗口大小或竞技场大小变化时重新计算布局
+fn update_layout(
+    mut resized: EventReader<WindowResized>,
+    primary_query: Query<&Window, With<PrimaryWindow>>,
+    arena: Res<Arena>,
+    mut layout: ResMut<Layout>,
+) {
+    if resized.read().last().is_none() && !arena.is_changed() {
+        return;
+    }
+    let Ok(window) = primary_query.get_single() else {
+        return;
+    };
+    layout.set_if_neq(Layout::new(window.size(), &arena, HUD_HEIGHT));
+}
+
+fn size_arena_background(
+    layout: Res<Layout>,
+    mut background: Query<&mut Transform, With<ArenaBackground>>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+    for mut transform in background.iter_mut() {
+        transform.translation = layout.center.extend(-1.0);
+        transform.scale = layout.size.extend(1.0);
+    }
+}
+
+// 缩圈或布局变化时重新摆放危险区
+fn size_danger_zone(
+    layout: Res<Layout>,
+    bounds: Res<Bounds>,
+    arena: Res<Arena>,
+    mut zones: Query<(&DangerZone, &mut Transform)>,
+) {
+    if !layout.is_changed() && !bounds.is_changed() {
+        return;
+    }
+    for (zone, mut transform) in zones.iter_mut() {
+        let (min, max) = zone.cells(&bounds, &arena);
+        let size = (max - min).max(Vec2::ZERO);
+        let center = layout.cell_to_world((min + max) / 2.0 - 0.5);
+        transform.translation = center.extend(-0.5);
+        transform.scale = (size * layout.cell).extend(1.0);
+    }
+}
+
+// 给新生成的蛇、食物、道具、障碍物、传送门和敌人加上精灵
+fn attach_sprites(
+    mut commands: Commands,
+    registry: Res<PowerUpRegistry>,
+    theme: Res<Theme>,
+    heads: Query<Entity, Added<SnakeHead>>,
+    blocks: Query<Entity, (Added<SnakeBlock>, Without<SnakeHead>)>,
+    snake: Query<(Entity, &Position), Added<SnakeBlock>>,
+    food: Query<(Entity, &Food), Added<Food>>,
+    power_ups: Query<(Entity, &PowerUp), Added<PowerUp>>,
+    obstacles: Query<Entity, Added<Obstacle>>,
+    portals: Query<Entity, Added<Portal>>,
+    enemies: Query<(Entity, &Position), Added<Enemy>>,
+) {
+    let mut attach = |entity: Entity, color: Color| {
+        commands.entity(entity).insert(SpriteBundle {
+            sprite: Sprite { color, ..default() },
+            ..default()
+        });
+    };
+    heads
+        .iter()
+        .for_each(|entity| attach(entity, theme.snake_head.0));
+    blocks
+        .iter()
+        .for_each(|entity| attach(entity, theme.snake_body.0));
+    food.iter()
+        .for_each(|(entity, food)| attach(entity, theme.food(food.0)));
+    power_ups
+        .iter()
+        .for_each(|(entity, power_up)| attach(entity, registry.0[power_up.0].color()));
+    obstacles
+        .iter()
+        .for_each(|entity| attach(entity, theme.obstacle.0));
+    portals
+        .iter()
+        .for_each(|entity| attach(entity, theme.portal.0));
+    enemies
+        .iter()
+        .for_each(|(entity, _)| attach(entity, theme.enemy.0));
+    // 敌人也在格子之间平滑移动
+    for (entity, pos) in snake.iter().chain(enemies.iter()) {
+        commands.entity(entity).insert(Tween::at(*pos));
+    }
+}
+
+// 把设置中和画面相关的选项同步到各个资源
+pub(crate) fn apply_settings(
+    settings: Res<Settings>,
+    registry: Res<ThemeRegistry>,
+    mut theme: ResMut<Theme>,
+    mut style: ResMut<MovementStyle>,
+    mut effects: ResMut<EffectsSettings>,
+    mut localizer: ResMut<Localizer>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    if theme.name != settings.theme {
+        match registry.find(&settings.theme) {
+            Some(chosen) => *theme = chosen.clone(),
+            None => warn!("没有名为 {} 的主题", settings.theme),
+        }
+    }
+    style.set_if_neq(if settings.smooth_movement {
+        MovementStyle::Smooth
+    } else {
+        MovementStyle::Snap
+    });
+    effects.set_if_neq(if settings.effects {
+        EffectsSettings::default()
+    } else {
+        EffectsSettings::off()
+    });
+    if localizer.language() != settings.language {
+        localizer.set_language(settings.language);
+    }
+}
+
+// 切换主题时给已有的精灵换色，新生成的精灵在 attach_sprites 中取当前主题的颜色
+fn apply_theme(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut sprites: Query<(
+        &mut Sprite,
+        Option<&Food>,
+        Has<SnakeHead>,
+        Has<SnakeBlock>,
+        Has<ArenaBackground>,
+        Has<Obstacle>,
+        Has<DangerZone>,
+        Has<Portal>,
+        Has<Enemy>,
+    )>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+    clear_color.0 = theme.background.0;
+    for (mut sprite, food, head, block, background, obstacle, danger, portal, enemy) in
+        sprites.iter_mut()
+    {
+        let color = match food {
+            Some(food) => theme.food(food.0),
+            None if head => theme.snake_head.0,
+            None if block => theme.snake_body.0,
+            None if background => theme.arena.0,
+            None if obstacle => theme.obstacle.0,
+            None if danger => theme.danger.0,
+            None if portal => theme.portal.0,
+            None if enemy => theme.enemy.0,
+            None => continue,
+        };
+        // 保留食物淡出时的透明度
+        sprite.color = color.with_alpha(sprite.color.alpha());
+    }
+}
+
+fn update_window_title(
+    localizer: Res<Localizer>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !localizer.is_changed() {
+        return;
+    }
+    for mut window in windows.iter_mut() {
+        window.title = localizer.get("window-title");
+    }
+}
+
+// F2 在平滑移动和逐格跳动之间切换，和设置界面中的选项是同一个设置
+fn toggle_movement_style(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keys.just_pressed(KeyCode::F2) {
+        settings.smooth_movement = !settings.smooth_movement;
+    }
+}
+
+// 记录蛇和敌人每一节走过的格子；敌人隔拍才走，被挡住时也不走，这些节拍要停在原地
+pub fn track_moves(
+    mut q: Query<(&Position, &mut Tween)>,
+    portals: Query<(&Position, &Portal), Without<Tween>>,
+) {
+    for (pos, mut tween) in q.iter_mut() {
+        if *pos == tween.to {
+            tween.settle();
+        } else {
+            let step = move_step(tween.to, *pos, &portals);
+            tween.d_in = tween.d_out;
+            tween.from = tween.to;
+            tween.to = *pos;
+            tween.d_out = step;
+        }
+    }
+}
+
+// 一次移动的方向：相邻的格子直接相减；不相邻时是穿过了传送门（入口在 from 旁边）或者穿墙
+fn move_step(
+    from: Position,
+    to: Position,
+    portals: &Query<(&Position, &Portal), Without<Tween>>,
+) -> Vec2 {
+    let d = cell(to) - cell(from);
+    if d.length_squared() <= 1.0 {
+        return d;
+    }
+    let entry = portals.iter().find(|(entry, portal)| {
+        portal.partner == to && (cell(**entry) - cell(from)).length_squared() == 1.0
+    });
+    if let Some((entry, _)) = entry {
+        return cell(*entry) - cell(from);
+    }
+    match (d.x, d.y) {
+        (x, 0.0) => Vec2::new(-x.signum(), 0.0),
+        (0.0, y) => Vec2::new(0.0, -y.signum()),
+        _ => Vec2::ZERO,
+    }
+}
+
+// 稀有食物一闪一闪，快消失的食物逐渐变透明
+fn blink_food(mut food: Query<(&Food, &FoodLifetime, &mut Sprite, &mut Visibility)>) {
+    for (food, lifetime, mut sprite, mut visibility) in food.iter_mut() {
+        sprite.color.set_alpha(lifetime.fade());
+        if food.0.blinks() {
+            visibility.set_if_neq(if lifetime.blink_visible() {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+        }
+    }
+}
+
+// 计算方块元素的大小
+pub fn size_scaling(layout: Res<Layout>, mut q: Query<(&Size, &mut Transform)>) {
+    for (sprite_size, mut transform) in q.iter_mut() {
+        transform.scale = Vec3::new(
+            sprite_size.width * layout.cell,
+            sprite_size.height * layout.cell,
+            1.0,
+        )
+    }
+}
+
+// 计算位移，平滑移动时按到下一个节拍的进度插值
+pub fn position_translation(
+    layout: Res<Layout>,
+    style: Res<MovementStyle>,
+    time: Res<Time<Fixed>>,
+    mut q: Query<(&Position, Option<&Tween>, &mut Transform)>,
+) {
+    for (pos, tween, mut transform) in q.iter_mut() {
+        let cell = match (*style, tween) {
+            (MovementStyle::Smooth, Some(tween)) => tween.sample(time.overstep_fraction()),
+            _ => Vec2::new(pos.x as f32, pos.y as f32),
+        };
+        transform.translation = layout.cell_to_world(cell).extend(0.0);
+    }
+}
@@ -0,0 +1,441 @@
+//! 存档：暂停或退出时保存正在进行的一局，从主菜单继续时原样恢复
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::control::GameControl;
+use crate::actions::{Position, Size};
+use crate::bounds::update_bounds;
+use crate::enemy::{spawn_enemies, spawn_enemy_at, Enemy};
+use crate::food::{spawn_food_at, Food, FoodKind, FoodLifetime, FoodSpawnTimer};
+use crate::mode::{reset_mode_clock, GameMode, ModeClock};
+use crate::obstacle::{spawn_obstacle_at, Obstacle};
+use crate::portal::{spawn_portal_pair, spawn_portals, Portal};
+use crate::powerup::{ActiveEffect, ActiveEffects, PowerUp, PowerUpRegistry, PowerUpSpawnTimer};
+use crate::score::{reset_combo, AddScoreEvent, Combo, Score, ScoreLog, ScoreSource};
+use crate::settings::{apply_arena, DataDir};
+use crate::snake::{
+    spawn_snake, spawn_snake_at, PendingGrowth, SnakeBlock, SnakeBlocks, SnakeHead,
+};
+use crate::tick::{reset_tick, GameTick};
+use crate::{Arena, GameRng, GameState, PauseState};
+
+pub struct SavePlugin;
+
+/// 存档格式的版本，存档的内容有不兼容的改动时加一
+pub const SAVE_VERSION: u32 = 7;
+
+/// 存档文件名，放在 `DataDir` 下
+pub const SAVE_FILE: &str = "save.ron";
+
+/// 读不出来的存档改成这个名字留着，不再出现“继续”
+pub const BROKEN_SAVE_FILE: &str = "save.broken.ron";
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedFood {
+    pub kind: FoodKind,
+    pub position: Position,
+    pub lifetime: Option<Timer>,
+}
+
+/// 道具按名字保存，道具列表调整顺序后仍然能读取
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedPowerUp {
+    pub name: String,
+    pub position: Position,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedEffect {
+    pub name: String,
+    pub stacks: u32,
+    pub timer: Timer,
+}
+
+/// 计分记录，去掉了存档中无法保存的蛇头实体
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SavedScore {
+    pub source: ScoreSource,
+    pub position: Position,
+    pub points: i32,
+    pub multiplier: u32,
+    pub streak: u32,
+}
+
+/// 一局游戏的完整状态
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub mode: GameMode,
+    pub clock: ModeClock,
+    pub arena: Arena,
+    /// 从头到尾
+    pub snake: Vec<Position>,
+    pub direction: GameControl,
+    pub growth: PendingGrowth,
+    pub food: Vec<SavedFood>,
+    pub power_ups: Vec<SavedPowerUp>,
+    pub obstacles: Vec<Position>,
+    /// 每对传送门保存一次
+    pub portals: Vec<(Position, Position)>,
+    pub enemies: Vec<(Position, Enemy)>,
+    pub effects: Vec<SavedEffect>,
+    pub score: u32,
+    pub combo: Combo,
+    pub log: Vec<SavedScore>,
+    pub tick: GameTick,
+    pub food_timer: Timer,
+    pub power_up_timer: Timer,
+    pub rng: GameRng,
+}
+
+/// 只读版本号，用于在解析整个存档之前检查版本
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    /// 存档由不兼容的版本写入
+    Version {
+        found: u32,
+        expected: u32,
+    },
+    /// 存档中的道具在当前的道具列表中不存在
+    UnknownPowerUp(String),
+    NoSnake,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "读写存档失败：{}", err),
+            Self::Parse(err) => write!(f, "存档格式错误：{}", err),
+            Self::Serialize(err) => write!(f, "保存存档失败：{}", err),
+            Self::Version { found, expected } => write!(
+                f,
+                "存档版本 {} 与当前版本 {} 不兼容，无法继续",
+                found, expected
+            ),
+            Self::UnknownPowerUp(name) => write!(f, "存档中的道具 {} 不存在", name),
+            Self::NoSnake => write!(f, "存档中没有蛇"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl SaveGame {
+    /// 保存当前这一局，场上没有蛇时返回 `None`
+    pub fn capture(world: &mut World) -> Option<Self> {
+        let blocks = world.resource::<SnakeBlocks>().0.clone();
+        let direction = world.get::<SnakeHead>(*blocks.first()?)?.direction;
+        let snake = blocks
+            .iter()
+            .filter_map(|&entity| world.get::<Position>(entity).copied())
+            .collect();
+        let food = world
+            .query::<(&Food, &Position, Option<&FoodLifetime>)>()
+            .iter(world)
+            .map(|(food, &position, lifetime)| SavedFood {
+                kind: food.0,
+                position,
+                lifetime: lifetime.map(|lifetime| lifetime.0.clone()),
+            })
+            .collect();
+        let power_ups = world
+            .query::<(&PowerUp, &Position)>()
+            .iter(world)
+            .map(|(power_up, &position)| (power_up.0, position))
+            .collect::<Vec<_>>();
+        let obstacles = world
+            .query_filtered::<&Position, With<Obstacle>>()
+            .iter(world)
+            .copied()
+            .collect();
+        let portals = world
+            .query::<(&Position, &Portal)>()
+            .iter(world)
+            .filter(|(pos, portal)| (pos.x, pos.y) < (portal.partner.x, portal.partner.y))
+            .map(|(&pos, portal)| (pos, portal.partner))
+            .collect();
+        let enemies = world
+            .query::<(&Position, &Enemy)>()
+            .iter(world)
+            .map(|(&pos, enemy)| (pos, enemy.clone()))
+            .collect();
+        let registry = world.resource::<PowerUpRegistry>();
+        let power_ups = power_ups
+            .into_iter()
+            .map(|(index, position)| SavedPowerUp {
+                name: registry.0[index].name.clone(),
+                position,
+            })
+            .collect();
+        let effects = world
+            .resource::<ActiveEffects>()
+            .0
+            .iter()
+            .map(|active| SavedEffect {
+                name: registry.0[active.def].name.clone(),
+                stacks: active.stacks,
+                timer: active.timer.clone(),
+            })
+            .collect();
+        let log = world
+            .resource::<ScoreLog>()
+            .0
+            .iter()
+            .map(|event| SavedScore {
+                source: event.source,
+                position: event.position,
+                points: event.points,
+                multiplier: event.multiplier,
+                streak: event.streak,
+            })
+            .collect();
+        Some(Self {
+            version: SAVE_VERSION,
+            mode: *world.resource::<GameMode>(),
+            clock: *world.resource::<ModeClock>(),
+            arena: *world.resource::<Arena>(),
+            snake,
+            direction,
+            growth: *world.resource::<PendingGrowth>(),
+            food,
+            power_ups,
+            obstacles,
+            portals,
+            enemies,
+            effects,
+            score: world.resource::<Score>().get(),
+            combo: world.resource::<Combo>().clone(),
+            log,
+            tick: *world.resource::<GameTick>(),
+            food_timer: world.resource::<FoodSpawnTimer>().0.clone(),
+            power_up_timer: world.resource::<PowerUpSpawnTimer>().0.clone(),
+            rng: world.resource::<GameRng>().clone(),
+        })
+    }
+
+    /// 用存档替换场上的蛇、食物、道具和计分状态
+    pub fn restore(self, world: &mut World) -> Result<(), SaveError> {
+        if self.snake.is_empty() {
+            return Err(SaveError::NoSnake);
+        }
+        let registry = world.resource::<PowerUpRegistry>();
+        let find = |name: &str| {
+            registry
+                .find(name)
+                .ok_or_else(|| SaveError::UnknownPowerUp(name.to_string()))
+        };
+        let power_ups = self
+            .power_ups
+            .iter()
+            .map(|saved| Ok((PowerUp(find(&saved.name)?), saved.position)))
+            .collect::<Result<Vec<_>, SaveError>>()?;
+        let effects = self
+            .effects
+            .iter()
+            .map(|saved| {
+                Ok(ActiveEffect {
+                    def: find(&saved.name)?,
+                    stacks: saved.stacks,
+                    timer: saved.timer.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, SaveError>>()?;
+
+        let old = world
+            .query_filtered::<Entity, Or<(
+                With<SnakeBlock>,
+                With<Food>,
+                With<PowerUp>,
+                With<Obstacle>,
+                With<Portal>,
+                With<Enemy>,
+            )>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        for entity in old {
+            world.despawn(entity);
+        }
+
+        let mut commands = world.commands();
+        let blocks = spawn_snake_at(&mut commands, &self.snake, self.direction);
+        let head = blocks[0];
+        for food in &self.food {
+            let entity = spawn_food_at(&mut commands, food.kind, food.position, None);
+            if let Some(timer) = &food.lifetime {
+                commands.entity(entity).insert(FoodLifetime(timer.clone()));
+            }
+        }
+        for (power_up, position) in power_ups {
+            commands.spawn((power_up, position, Size::square(0.8)));
+        }
+        for &pos in &self.obstacles {
+            spawn_obstacle_at(&mut commands, pos);
+        }
+        for &(a, b) in &self.portals {
+            spawn_portal_pair(&mut commands, a, b);
+        }
+        for (pos, enemy) in &self.enemies {
+            spawn_enemy_at(&mut commands, enemy.clone(), *pos);
+        }
+        world.flush();
+
+        world.insert_resource(blocks);
+        world.insert_resource(self.growth);
+        world.insert_resource(self.mode);
+        world.insert_resource(self.clock);
+        world.insert_resource(self.arena);
+        world.insert_resource(ActiveEffects(effects));
+        world.resource_mut::<Score>().set(self.score);
+        world.insert_resource(self.combo);
+        world.insert_resource(ScoreLog(
+            self.log
+                .iter()
+                .map(|saved| AddScoreEvent {
+                    snake: head,
+                    source: saved.source,
+                    position: saved.position,
+                    points: saved.points,
+                    multiplier: saved.multiplier,
+                    streak: saved.streak,
+                })
+                .collect(),
+        ));
+        world.insert_resource(self.tick);
+        world.insert_resource(FoodSpawnTimer(self.food_timer));
+        world.insert_resource(PowerUpSpawnTimer(self.power_up_timer));
+        world.insert_resource(self.rng);
+        Ok(())
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SaveError::Serialize)
+    }
+
+    /// 先检查版本号，版本不同时不再解析其余内容
+    pub fn from_ron(source: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = ron::from_str(source).map_err(SaveError::Parse)?;
+        if header.version != SAVE_VERSION {
+            return Err(SaveError::Version {
+                found: header.version,
+                expected: SAVE_VERSION,
+            });
+        }
+        ron::from_str(source).map_err(SaveError::Parse)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        Self::from_ron(&std::fs::read_to_string(path).map_err(SaveError::Io)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SaveError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(SaveError::Io)?;
+        }
+        std::fs::write(path, self.to_ron()?).map_err(SaveError::Io)
+    }
+}
+
+/// 进入 `Playing` 时用这个存档代替新的一局
+#[derive(Resource)]
+pub struct PendingRestore(pub SaveGame);
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            start_game
+                .after(spawn_snake)
+                .after(reset_combo)
+                .after(reset_mode_clock)
+                .after(reset_tick)
+                .after(spawn_portals)
+                .after(spawn_enemies)
+                .before(update_bounds)
+                .after(apply_arena),
+        )
+        .add_systems(OnEnter(PauseState::Paused), autosave)
+        .add_systems(OnEnter(GameState::GameOver), delete_save)
+        .add_systems(Last, autosave_on_exit);
+    }
+}
+
+// 有待恢复的存档时恢复存档，否则是新的一局，旧的存档作废
+fn start_game(world: &mut World) {
+    let Some(PendingRestore(save)) = world.remove_resource::<PendingRestore>() else {
+        remove_save_file(world.resource::<DataDir>());
+        return;
+    };
+    if let Err(err) = save.restore(world) {
+        error!("{}，开始新的一局", err);
+        set_aside_save(world.resource::<DataDir>());
+    }
+}
+
+fn autosave(world: &mut World) {
+    let Some(path) = world.resource::<DataDir>().file(SAVE_FILE) else {
+        return;
+    };
+    let Some(save) = SaveGame::capture(world) else {
+        return;
+    };
+    match save.save(&path) {
+        Ok(()) => info!("已保存到 {}", path.display()),
+        Err(err) => warn!("{}", err),
+    }
+}
+
+// 游戏中关闭窗口时保存
+fn autosave_on_exit(world: &mut World) {
+    let exiting = world
+        .get_resource::<Events<AppExit>>()
+        .is_some_and(|events| !events.is_empty());
+    let playing = world
+        .get_resource::<State<GameState>>()
+        .is_some_and(|state| *state.get() == GameState::Playing);
+    if exiting && playing {
+        autosave(world);
+    }
+}
+
+// 死亡后这一局不能再继续
+fn delete_save(dir: Res<DataDir>) {
+    remove_save_file(&dir);
+}
+
+/// 把读取或恢复失败的存档改名留着，覆盖上一个读不出来的存档
+pub fn set_aside_save(dir: &DataDir) {
+    let (Some(path), Some(broken)) = (dir.file(SAVE_FILE), dir.file(BROKEN_SAVE_FILE)) else {
+        return;
+    };
+    if let Err(err) = std::fs::rename(&path, &broken) {
+        if err.kind() != io::ErrorKind::NotFound {
+            warn!("移走存档失败：{}", err);
+        }
+    }
+}
+
+fn remove_save_file(dir: &DataDir) {
+    let Some(path) = dir.file(SAVE_FILE) else {
+        return;
+    };
+    if let Err(err) = std::fs::remove_file(&path) {
+        if err.kind() != io::ErrorKind::NotFound {
+            warn!("删除存档失败：{}", err);
+        }
+    }
+}
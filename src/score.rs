@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::Position;
+use crate::food::FoodKind;
+use crate::snake::snake_eating;
+use crate::tick::GameSet;
+use crate::GameState;
+
+pub struct ScorePlugin;
+
+#[derive(Default, Resource)]
+pub struct Score(u32);
+
+/// 得分的来源
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum ScoreSource {
+    /// 吃掉的食物
+    Food(FoodKind),
+    /// 生存模式的存活时间
+    Survival,
+}
+
+impl ScoreSource {
+    /// 得分明细中的顺序
+    pub fn all() -> impl Iterator<Item = ScoreSource> {
+        FoodKind::ALL
+            .map(Self::Food)
+            .into_iter()
+            .chain([Self::Survival])
+    }
+
+    /// 本地化文本的键名
+    pub fn key(self) -> String {
+        match self {
+            Self::Food(kind) => format!("food-{:?}", kind),
+            Self::Survival => "score-Survival".to_string(),
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct AddScoreEvent {
+    /// 得分的蛇（蛇头实体）
+    pub snake: Entity,
+    pub source: ScoreSource,
+    /// 食物被吃掉的位置，存活加分时是蛇头的位置
+    pub position: Position,
+    /// 基础分数，负数为扣分
+    pub points: i32,
+    /// 道具和连击带来的倍率
+    pub multiplier: u32,
+    /// 吃到这个食物时的连击数，存活加分时为 0
+    pub streak: u32,
+}
+
+impl AddScoreEvent {
+    /// 乘上倍率后实际加的分数
+    pub fn total(&self) -> i32 {
+        self.points * self.multiplier as i32
+    }
+}
+
+/// 连击规则：两次进食间隔不超过 `window` 次移动算作连击，
+/// 连击数每增加 `step` 倍率加一，最高 `max_multiplier`
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct ComboRules {
+    pub window: u32,
+    pub step: u32,
+    pub max_multiplier: u32,
+}
+
+impl Default for ComboRules {
+    fn default() -> Self {
+        Self {
+            window: 10,
+            step: 1,
+            max_multiplier: 5,
+        }
+    }
+}
+
+impl ComboRules {
+    /// 不计连击倍率，连击数照常统计
+    pub fn off() -> Self {
+        Self {
+            max_multiplier: 1,
+            ..default()
+        }
+    }
+
+    pub fn multiplier(&self, streak: u32) -> u32 {
+        (1 + streak.saturating_sub(1) / self.step.max(1)).clamp(1, self.max_multiplier.max(1))
+    }
+}
+
+/// 当前连击状态
+#[derive(Resource, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Combo {
+    pub streak: u32,
+    /// 这一局最长的连击
+    pub best: u32,
+    /// 距离上次进食的移动次数
+    ticks_since_eat: u32,
+}
+
+impl Combo {
+    /// 吃到加分的食物，返回连击倍率
+    pub fn hit(&mut self, rules: &ComboRules) -> u32 {
+        self.streak = if self.ticks_since_eat <= rules.window {
+            self.streak + 1
+        } else {
+            1
+        };
+        self.ticks_since_eat = 0;
+        self.best = self.best.max(self.streak);
+        rules.multiplier(self.streak)
+    }
+
+    /// 吃到扣分的食物时连击中断
+    pub fn break_streak(&mut self) {
+        self.streak = 0;
+    }
+
+    fn tick(&mut self, rules: &ComboRules) {
+        self.ticks_since_eat = self.ticks_since_eat.saturating_add(1);
+        if self.ticks_since_eat > rules.window {
+            self.streak = 0;
+        }
+    }
+}
+
+/// 这一局所有的计分事件，用于结束时的得分明细
+#[derive(Resource, Default)]
+pub struct ScoreLog(pub Vec<AddScoreEvent>);
+
+impl ScoreLog {
+    /// 按来源汇总：（来源，次数，得分），没有得分的来源不列出；各项得分之和就是总分
+    pub fn by_source(&self) -> Vec<(ScoreSource, u32, i32)> {
+        ScoreSource::all()
+            .filter_map(|source| {
+                let events = self.0.iter().filter(|event| event.source == source);
+                let count = events.clone().count() as u32;
+                (count > 0).then(|| (source, count, events.map(AddScoreEvent::total).sum()))
+            })
+            .collect()
+    }
+}
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Score::default())
+            .init_resource::<ComboRules>()
+            .init_resource::<Combo>()
+            .init_resource::<ScoreLog>()
+            .add_event::<AddScoreEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_combo.before(snake_eating),
+                    add_score.after(snake_eating),
+                )
+                    .in_set(GameSet::Eat),
+            )
+            .add_systems(OnEnter(GameState::Playing), reset_combo)
+            .add_systems(OnEnter(GameState::GameOver), reset_score)
+            .add_systems(OnEnter(GameState::Menu), reset_score);
+    }
+}
+
+impl Score {
+    fn reset(&mut self) {
+        self.0 = 0;
+    }
+
+    // 分数最低为 0
+    pub(crate) fn add(&mut self, points: i32) {
+        self.0 = self.0.saturating_add_signed(points);
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    /// 恢复存档时使用
+    pub(crate) fn set(&mut self, value: u32) {
+        self.0 = value;
+    }
+}
+
+pub fn add_score(
+    mut score: ResMut<Score>,
+    mut log: ResMut<ScoreLog>,
+    mut reader: EventReader<AddScoreEvent>,
+) {
+    // 同一帧可能吃到多个食物，每个事件都要计分
+    for event in reader.read() {
+        score.add(event.total());
+        log.0.push(*event);
+    }
+}
+
+// 每次移动后累计间隔，超出连击窗口时连击中断
+fn tick_combo(rules: Res<ComboRules>, mut combo: ResMut<Combo>) {
+    combo.tick(&rules);
+}
+
+// 连击和得分明细保留到结束界面，开始新的一局时才清空
+pub fn reset_combo(mut combo: ResMut<Combo>, mut log: ResMut<ScoreLog>) {
+    *combo = Combo::default();
+    log.0.clear();
+}
+
+pub fn reset_score(mut score: ResMut<Score>) {
+    score.reset();
+}
@@ -0,0 +1,278 @@
+//! 用户设置：启动时从配置文件读取，修改后立即写回
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::control::KeyBindings;
+use crate::enemy::{EnemyConfig, RANDOM_ENEMIES};
+use crate::i18n::Language;
+use crate::portal::{PortalConfig, RANDOM_PORTAL_PAIRS};
+use crate::snake::{CollisionPolicy, WallMode};
+use crate::tick::TickInterval;
+use crate::{Arena, GameState};
+
+pub struct SettingsPlugin;
+
+/// 竞技场大小的几个预设
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum ArenaSize {
+    Small,
+    #[default]
+    Normal,
+    Large,
+    /// 宽屏，宽比高大
+    Wide,
+}
+
+impl ArenaSize {
+    pub const ALL: [ArenaSize; 4] = [Self::Small, Self::Normal, Self::Large, Self::Wide];
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self)
+    }
+
+    pub fn arena(self) -> Arena {
+        let (width, height) = match self {
+            Self::Small => (15, 15),
+            Self::Normal => (25, 25),
+            Self::Large => (35, 35),
+            Self::Wide => (40, 25),
+        };
+        Arena { width, height }
+    }
+}
+
+/// 蛇的速度，也就是难度
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Speed {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl Speed {
+    pub const ALL: [Speed; 3] = [Self::Slow, Self::Normal, Self::Fast];
+
+    pub fn next(self) -> Self {
+        cycle(&Self::ALL, self)
+    }
+
+    /// 每次移动间隔的秒数
+    pub fn move_interval(self) -> f32 {
+        match self {
+            Self::Slow => 0.3,
+            Self::Normal => 0.2,
+            Self::Fast => 0.12,
+        }
+    }
+}
+
+// 在列表中取 `current` 的下一项，到末尾后回到第一项
+fn cycle<T: PartialEq + Copy>(all: &[T], current: T) -> T {
+    let index = all.iter().position(|&item| item == current).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub arena: ArenaSize,
+    pub speed: Speed,
+    pub wall_mode: WallMode,
+    pub collision: CollisionPolicy,
+    /// 主题名，见 `assets/themes.ron`
+    pub theme: String,
+    /// 0~1
+    pub volume: f32,
+    pub language: Language,
+    pub key_bindings: KeyBindings,
+    pub show_fps: bool,
+    /// 粒子、镜头抖动和闪屏
+    pub effects: bool,
+    /// 蛇在格子之间平滑移动
+    pub smooth_movement: bool,
+    /// 每局随机放置传送门
+    pub portals: bool,
+    /// 每局随机放置巡逻块、游荡者和追击者
+    pub enemies: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            arena: ArenaSize::default(),
+            speed: Speed::default(),
+            wall_mode: WallMode::default(),
+            collision: CollisionPolicy::default(),
+            theme: "Classic".to_string(),
+            volume: 1.0,
+            language: Language::default(),
+            key_bindings: KeyBindings::default(),
+            show_fps: false,
+            effects: true,
+            smooth_movement: false,
+            portals: false,
+            enemies: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "读写配置文件失败：{}", err),
+            Self::Parse(err) => write!(f, "配置文件格式错误：{}", err),
+            Self::Serialize(err) => write!(f, "保存配置失败：{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl Settings {
+    pub fn from_ron(source: &str) -> Result<Self, SettingsError> {
+        ron::from_str(source).map_err(SettingsError::Parse)
+    }
+
+    pub fn to_ron(&self) -> Result<String, SettingsError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SettingsError::Serialize)
+    }
+
+    /// 文件不存在时返回默认设置
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        match std::fs::read_to_string(path) {
+            Ok(source) => Self::from_ron(&source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(SettingsError::Io(err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(SettingsError::Io)?;
+        }
+        std::fs::write(path, self.to_ron()?).map_err(SettingsError::Io)
+    }
+}
+
+/// 配置文件的位置，为 `None` 时不读写文件（如测试中）
+#[derive(Resource, Clone, Default, Debug)]
+pub struct SettingsFile(pub Option<PathBuf>);
+
+impl SettingsFile {
+    /// 用户配置目录下的 `snake/settings.ron`
+    pub fn user() -> Self {
+        Self(dirs::config_dir().map(|dir| dir.join("snake").join("settings.ron")))
+    }
+
+    /// 读取设置，读取失败时打印警告并使用默认设置
+    pub fn load(&self) -> Settings {
+        let Some(path) = &self.0 else {
+            return Settings::default();
+        };
+        Settings::load(path).unwrap_or_else(|err| {
+            warn!("{}，使用默认设置", err);
+            Settings::default()
+        })
+    }
+}
+
+/// 存放排行榜等游戏数据的目录，为 `None` 时不读写文件（如测试中）
+#[derive(Resource, Clone, Default, Debug)]
+pub struct DataDir(pub Option<PathBuf>);
+
+impl DataDir {
+    /// 用户数据目录下的 `snake/`
+    pub fn user() -> Self {
+        Self(dirs::data_dir().map(|dir| dir.join("snake")))
+    }
+
+    pub fn file(&self, name: &str) -> Option<PathBuf> {
+        self.0.as_ref().map(|dir| dir.join(name))
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>()
+            .init_resource::<SettingsFile>()
+            .init_resource::<DataDir>()
+            .add_systems(PreUpdate, (apply_settings, save_settings))
+            .add_systems(OnEnter(GameState::Playing), apply_arena);
+
+        #[cfg(feature = "audio")]
+        app.add_systems(PreUpdate, apply_volume);
+    }
+}
+
+// 设置变化时更新规则相关的资源；竞技场大小要到下一局开始才生效
+fn apply_settings(
+    settings: Res<Settings>,
+    state: Res<State<GameState>>,
+    mut arena: ResMut<Arena>,
+    mut interval: ResMut<TickInterval>,
+    mut wall_mode: ResMut<WallMode>,
+    mut collision: ResMut<CollisionPolicy>,
+    mut bindings: ResMut<KeyBindings>,
+    mut portals: ResMut<PortalConfig>,
+    mut enemies: ResMut<EnemyConfig>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    if *state.get() != GameState::Playing {
+        arena.set_if_neq(settings.arena.arena());
+    }
+    interval.set_if_neq(TickInterval(Duration::from_secs_f32(
+        settings.speed.move_interval(),
+    )));
+    wall_mode.set_if_neq(settings.wall_mode);
+    collision.set_if_neq(settings.collision);
+    bindings.set_if_neq(settings.key_bindings.clone());
+    portals.random_pairs = if settings.portals {
+        RANDOM_PORTAL_PAIRS
+    } else {
+        0
+    };
+    let count = if settings.enemies { RANDOM_ENEMIES } else { 0 };
+    enemies.random_patrols = count;
+    enemies.wanderers = count;
+    enemies.chasers = count;
+}
+
+pub fn apply_arena(settings: Res<Settings>, mut arena: ResMut<Arena>) {
+    arena.set_if_neq(settings.arena.arena());
+}
+
+fn save_settings(settings: Res<Settings>, file: Res<SettingsFile>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    if let Some(path) = &file.0 {
+        if let Err(err) = settings.save(path) {
+            warn!("{}", err);
+        }
+    }
+}
+
+// 音量只影响之后开始播放的声音
+#[cfg(feature = "audio")]
+fn apply_volume(settings: Res<Settings>, volume: Option<ResMut<bevy::audio::GlobalVolume>>) {
+    if let Some(mut volume) = volume.filter(|_| settings.is_changed()) {
+        *volume = bevy::audio::GlobalVolume::new(settings.volume);
+    }
+}
@@ -1,9 +1,17 @@
-use crate::actions::control::GameControl;
+use crate::actions::control::{GameControl, KeyBindings};
 use crate::actions::{Position, Size};
-use crate::board::AddScoreEvent;
-use crate::food::Food;
-use crate::{GameState, ARENA_HEIGHT, ARENA_WIDTH, SNAKE_BODY_COLOR, SNAKE_HEAD_COLOR};
+use crate::food::{Food, FoodKind};
+use crate::powerup::{EffectModifiers, PowerUp, PowerUpRegistry};
+use crate::score::{AddScoreEvent, Combo, ComboRules, ScoreSource};
+use crate::bounds::Bounds;
+use crate::enemy::Enemy;
+use crate::gameover::{GameOverCause, GameOverEvent};
+use crate::obstacle::Obstacle;
+use crate::portal::Portal;
+use crate::tick::{GameSet, GameTick};
+use crate::{Arena, GameState, PauseState};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct SnakePlugin;
 
@@ -18,126 +26,218 @@ pub struct SnakeBlock;
 #[derive(Resource, Default, DerefMut, Deref)]
 pub struct SnakeBlocks(pub Vec<Entity>);
 
+/// 这一拍尾巴离开的格子，这一拍没有移动时为 `None`；新长的一节放在这里
 #[derive(Default, Resource)]
 pub struct LastBlockPosition(pub Option<Position>);
 
-#[derive(Resource)]
-pub struct SnakeMoveTimer(pub Timer);
+/// 还没长出来的节数，每个节拍长一节
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PendingGrowth(pub u32);
 
-#[derive(Event)]
-pub struct GrowthEvent;
+/// 撞到竞技场边缘时的规则
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum WallMode {
+    /// 撞墙结束游戏
+    #[default]
+    Solid,
+    /// 从另一边穿出来
+    Wrap,
+}
+
+impl WallMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Solid => Self::Wrap,
+            Self::Wrap => Self::Solid,
+        }
+    }
+}
+
+/// 蛇头走进尾巴所在的格子时是否算撞到自己
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CollisionPolicy {
+    /// 尾巴先离开：可以走进尾巴正要离开的格子；这一拍吃到食物变长时尾巴不动，仍然算撞到自己
+    #[default]
+    TailVacates,
+    /// 移动前蛇身占着的格子都不能走，包括尾巴
+    Strict,
+}
+
+impl CollisionPolicy {
+    pub fn next(self) -> Self {
+        match self {
+            Self::TailVacates => Self::Strict,
+            Self::Strict => Self::TailVacates,
+        }
+    }
+
+    /// 这一拍蛇头不能走进的格子；`body` 是移动前从头到尾的位置，`grows` 表示这一拍会变长
+    pub fn blocked(self, body: &[Position], grows: bool) -> &[Position] {
+        match self {
+            Self::TailVacates if !grows => &body[..body.len() - 1],
+            _ => body,
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct GrowthEvent {
+    /// 生长的蛇（蛇头实体）
+    pub snake: Entity,
+    /// 增加的节数，负数表示从尾部去掉
+    pub amount: i32,
+    pub source: GrowthSource,
+}
+
+/// 生长或变短的原因
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrowthSource {
+    Food(FoodKind),
+    /// 道具，值为它在 `PowerUpRegistry` 中的下标
+    PowerUp(usize),
+}
 
 impl Plugin for SnakePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(SnakeMoveTimer::new(0.20))
-            .insert_resource(SnakeBlocks::default())
+        app.insert_resource(SnakeBlocks::default())
             .insert_resource(LastBlockPosition::default())
+            .init_resource::<PendingGrowth>()
+            .init_resource::<WallMode>()
+            .init_resource::<CollisionPolicy>()
+            .init_resource::<KeyBindings>()
             .add_event::<GrowthEvent>()
             .add_systems(OnEnter(GameState::Playing), spawn_snake)
             .add_systems(
-                Update,
+                PreUpdate,
+                snake_movment_input
+                    .in_set(GameSet::Input)
+                    .run_if(in_state(PauseState::Running)),
+            )
+            // 吃到食物后当拍计分、当拍生长
+            .add_systems(
+                FixedUpdate,
                 (
-                    snake_movment_input
-                        .run_if(in_state(GameState::Playing))
-                        .before(move_snake),
-                    move_snake.run_if(in_state(GameState::Playing)),
-                    snake_eating.run_if(in_state(GameState::Playing)),
-                    snake_growth.run_if(in_state(GameState::Playing)),
+                    move_snake.in_set(GameSet::Move),
+                    snake_eating.in_set(GameSet::Eat),
+                    snake_growth.in_set(GameSet::Grow),
                 ),
             );
     }
 }
 
-impl SnakeMoveTimer {
-    pub fn new(d: f32) -> Self {
-        Self(Timer::from_seconds(d, TimerMode::Repeating))
-    }
+pub fn spawn_snake(
+    mut cmds: Commands,
+    mut blocks: ResMut<SnakeBlocks>,
+    mut pending: ResMut<PendingGrowth>,
+) {
+    *pending = PendingGrowth::default();
+    *blocks = spawn_snake_at(
+        &mut cmds,
+        &[Position { x: 3, y: 3 }, Position { x: 3, y: 2 }],
+        GameControl::default(),
+    );
 }
 
-pub fn spawn_snake(mut cmds: Commands, mut blocks: ResMut<SnakeBlocks>) {
-    *blocks = SnakeBlocks(vec![
-        cmds.spawn(SpriteBundle {
-            sprite: Sprite {
-                color: SNAKE_HEAD_COLOR,
-                ..default()
-            },
-            transform: Transform {
-                scale: Vec3::new(10.0, 10.0, 10.0),
-                ..default()
-            },
-            ..default()
-        })
-        .insert(SnakeHead {
-            direction: GameControl::default(),
-        })
+/// 按从头到尾的位置生成一条蛇，`segments` 不能为空
+pub fn spawn_snake_at(
+    cmds: &mut Commands,
+    segments: &[Position],
+    direction: GameControl,
+) -> SnakeBlocks {
+    let head = cmds
+        .spawn(SnakeHead { direction })
         .insert(SnakeBlock)
-        .insert(Position { x: 3, y: 3 })
+        .insert(segments[0])
         .insert(Size::square(0.8))
-        .id(),
-        spawn_block(cmds, Position { x: 3, y: 2 }),
-    ]);
+        .id();
+    let body = segments[1..].iter().map(|&pos| spawn_block(cmds, pos));
+    SnakeBlocks(std::iter::once(head).chain(body).collect())
 }
 
-fn spawn_block(mut cmds: Commands, pos: Position) -> Entity {
-    cmds.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: SNAKE_BODY_COLOR,
-            ..default()
-        },
-        ..default()
-    })
-    .insert(SnakeBlock)
-    .insert(pos)
-    .insert(Size::square(0.65))
-    .id()
+fn spawn_block(cmds: &mut Commands, pos: Position) -> Entity {
+    cmds.spawn(SnakeBlock)
+        .insert(pos)
+        .insert(Size::square(0.65))
+        .id()
 }
 
+// 先算出蛇头要去的格子，撞到东西时这一拍不移动，发出结束事件
 pub fn move_snake(
     mut heads: Query<(Entity, &mut SnakeHead)>,
     blocks: ResMut<SnakeBlocks>,
-    mut positions: Query<&mut Position>,
+    mut positions: Query<&mut Position, With<SnakeBlock>>,
+    portals: Query<(&Position, &Portal), Without<SnakeBlock>>,
+    solids: Query<
+        (&Position, Has<Enemy>),
+        (Or<(With<Obstacle>, With<Enemy>)>, Without<SnakeBlock>),
+    >,
     mut last_block_pos: ResMut<LastBlockPosition>,
-    time: Res<Time>,
-    mut timer: ResMut<SnakeMoveTimer>,
-    mut next_state: ResMut<NextState<GameState>>,
+    modifiers: Res<EffectModifiers>,
+    (arena, bounds, wall_mode): (Res<Arena>, Res<Bounds>, Res<WallMode>),
+    (policy, pending): (Res<CollisionPolicy>, Res<PendingGrowth>),
+    (food, power_ups, registry): (
+        Query<(&Position, &Food), Without<SnakeBlock>>,
+        Query<(&Position, &PowerUp), Without<SnakeBlock>>,
+        Res<PowerUpRegistry>,
+    ),
+    tick: Res<GameTick>,
+    mut game_over: EventWriter<GameOverEvent>,
 ) {
-    if !timer.0.tick(time.delta()).finished() {
-        return;
-    }
-
+    last_block_pos.0 = None;
     if let Some((entity, head)) = heads.iter_mut().next() {
         let block_positions = blocks
             .iter()
             .map(|e| *positions.get_mut(*e).unwrap())
             .collect::<Vec<Position>>();
+        let mut end = |cause, position| {
+            game_over.send(GameOverEvent {
+                cause,
+                tick: tick.0,
+                position,
+            });
+        };
         // 蛇头的移动
-        let mut head_pos = positions.get_mut(entity).unwrap();
-        match &head.direction {
-            GameControl::Left => {
-                head_pos.x -= 1;
-            }
-            GameControl::Right => {
-                head_pos.x += 1;
-            }
-            GameControl::Up => {
-                head_pos.y += 1;
-            }
-            GameControl::Down => {
-                head_pos.y -= 1;
+        let mut next = head.direction.step(block_positions[0]);
+        // 撞墙判断，穿墙模式下从另一边出来；缩圈后范围外的格子是危险区
+        if !bounds.contains(next) {
+            match *wall_mode {
+                WallMode::Solid if arena.contains(next) => {
+                    return end(GameOverCause::DangerZone, next);
+                }
+                WallMode::Solid => return end(GameOverCause::Wall, next),
+                WallMode::Wrap => next = bounds.wrap(next),
             }
-        };
-        // 撞墙判断
-        if head_pos.x < 0
-            || head_pos.y < 0
-            || head_pos.x as u32 >= ARENA_WIDTH
-            || head_pos.y as u32 >= ARENA_HEIGHT
-        {
-            next_state.set(GameState::GameOver);
         }
-        // 撞自身判断
-        if block_positions.contains(&head_pos) {
-            next_state.set(GameState::GameOver);
+        // 走进传送门时从另一个传送门出来，方向不变
+        if let Some((_, portal)) = portals.iter().find(|(pos, _)| **pos == next) {
+            next = portal.partner;
+        }
+        if let Some((_, enemy)) = solids.iter().find(|(pos, _)| **pos == next) {
+            let cause = if enemy {
+                GameOverCause::Enemy
+            } else {
+                GameOverCause::Obstacle
+            };
+            return end(cause, next);
         }
+        // 撞自身判断，幽灵状态下可以穿过自己；这一拍要长一节时尾巴不动。
+        // 这一拍的长度变化只来自蛇头走进的格子上的食物和道具，磁铁不会把食物拉到蛇头上
+        let eaten = food
+            .iter()
+            .filter(|(pos, _)| **pos == next)
+            .map(|(_, food)| food.0.growth())
+            .chain(
+                power_ups
+                    .iter()
+                    .filter(|(pos, _)| **pos == next)
+                    .map(|(_, power_up)| registry.0[power_up.0].growth()),
+            )
+            .sum::<i32>();
+        let grows = pending.0 as i32 + eaten > 0;
+        if !modifiers.ghost && policy.blocked(&block_positions, grows).contains(&next) {
+            return end(GameOverCause::Body, next);
+        }
+        *positions.get_mut(entity).unwrap() = next;
         // 蛇身的移动
         block_positions
             .iter()
@@ -145,16 +245,17 @@ pub fn move_snake(
             .for_each(|(pos, block)| {
                 *positions.get_mut(*block).unwrap() = *pos;
             });
-        *last_block_pos = LastBlockPosition(Some(*block_positions.last().unwrap()))
+        last_block_pos.0 = block_positions.last().copied();
     }
 }
 
 pub fn snake_movment_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
     mut heads: Query<&mut SnakeHead>,
 ) {
     if let Some(mut head) = heads.iter_mut().next() {
-        let dir: GameControl = GameControl::pressed(&keyboard_input, &head);
+        let dir: GameControl = GameControl::pressed(&keyboard_input, &bindings, &head);
         // 按运行反方向不生效
         if dir != head.direction.opposite() {
             head.direction = dir;
@@ -166,28 +267,74 @@ pub fn snake_eating(
     mut cmds: Commands,
     mut growth_writer: EventWriter<GrowthEvent>,
     mut score_writer: EventWriter<AddScoreEvent>,
-    food_positions: Query<(Entity, &Position), With<Food>>,
-    head_positions: Query<&Position, With<SnakeHead>>,
+    modifiers: Res<EffectModifiers>,
+    rules: Res<ComboRules>,
+    mut combo: ResMut<Combo>,
+    food_positions: Query<(Entity, &Food, &Position)>,
+    head_positions: Query<(Entity, &Position), With<SnakeHead>>,
 ) {
-    for head_pos in head_positions.iter() {
-        for (entity, food_pos) in food_positions.iter() {
+    for (snake, head_pos) in head_positions.iter() {
+        for (entity, food, food_pos) in food_positions.iter() {
             // 实物和头部位置重合，说明吃到实物
             if food_pos == head_pos {
                 cmds.entity(entity).despawn();
-                score_writer.send(AddScoreEvent);
-                growth_writer.send(GrowthEvent);
+                // 扣分的食物中断连击，也不吃连击倍率
+                let points = food.0.score();
+                let combo_multiplier = if points > 0 {
+                    combo.hit(&rules)
+                } else {
+                    combo.break_streak();
+                    1
+                };
+                score_writer.send(AddScoreEvent {
+                    snake,
+                    source: ScoreSource::Food(food.0),
+                    position: *food_pos,
+                    points,
+                    multiplier: modifiers.score_multiplier * combo_multiplier,
+                    streak: combo.streak,
+                });
+                growth_writer.send(GrowthEvent {
+                    snake,
+                    amount: food.0.growth(),
+                    source: GrowthSource::Food(food.0),
+                });
             }
         }
     }
 }
 
+// 同一拍的生长先合起来：变长的节数排进队列，变短时先抵消队列中还没长出来的，再从尾部去掉；
+// 队列中的节数每拍长一节，放在尾巴这一拍离开的格子上，所以吃到食物的这一拍就变长
 pub fn snake_growth(
-    cmds: Commands,
+    mut cmds: Commands,
     last_block_pos: Res<LastBlockPosition>,
+    mut pending: ResMut<PendingGrowth>,
     mut blocks: ResMut<SnakeBlocks>,
     mut growth_reader: EventReader<GrowthEvent>,
 ) {
-    if growth_reader.read().next().is_some() {
-        blocks.push(spawn_block(cmds, last_block_pos.0.unwrap()));
+    let amount = growth_reader.read().map(|event| event.amount).sum::<i32>();
+    if amount >= 0 {
+        pending.0 += amount as u32;
+    } else {
+        let cut = amount.unsigned_abs();
+        let cancelled = cut.min(pending.0);
+        pending.0 -= cancelled;
+        // 至少保留蛇头和一节身体
+        let keep = blocks
+            .len()
+            .saturating_sub((cut - cancelled) as usize)
+            .max(2);
+        if keep < blocks.len() {
+            for block in blocks.drain(keep..) {
+                cmds.entity(block).despawn();
+            }
+        }
+    }
+    if pending.0 > 0 {
+        if let Some(pos) = last_block_pos.0 {
+            blocks.push(spawn_block(&mut cmds, pos));
+            pending.0 -= 1;
+        }
     }
 }
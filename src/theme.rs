@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::food::FoodKind;
+use crate::settings::Settings;
+
+pub struct ThemePlugin;
+
+/// RON 中写作 `(r, g, b)`，分量为 0~1 的 sRGB
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(from = "(f32, f32, f32)")]
+pub struct Rgb(pub Color);
+
+impl From<(f32, f32, f32)> for Rgb {
+    fn from((r, g, b): (f32, f32, f32)) -> Self {
+        Self(Color::srgb(r, g, b))
+    }
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct FoodColors {
+    pub normal: Rgb,
+    pub golden: Rgb,
+    pub rare: Rgb,
+    pub poison: Rgb,
+}
+
+/// 一套配色，见 `assets/themes.ron`；作为资源时表示当前使用的主题
+#[derive(Resource, Deserialize, Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub background: Rgb,
+    pub arena: Rgb,
+    pub snake_head: Rgb,
+    pub snake_body: Rgb,
+    pub food: FoodColors,
+    pub obstacle: Rgb,
+    pub danger: Rgb,
+    pub portal: Rgb,
+    pub enemy: Rgb,
+    pub button: Rgb,
+    pub button_hovered: Rgb,
+    pub text: Rgb,
+    pub accent: Rgb,
+}
+
+impl Theme {
+    pub fn food(&self, kind: FoodKind) -> Color {
+        match kind {
+            FoodKind::Normal => self.food.normal.0,
+            FoodKind::Golden => self.food.golden.0,
+            FoodKind::Rare => self.food.rare.0,
+            FoodKind::Poison => self.food.poison.0,
+        }
+    }
+}
+
+/// 所有可选的主题
+#[derive(Resource)]
+pub struct ThemeRegistry(pub Vec<Theme>);
+
+impl ThemeRegistry {
+    pub fn from_ron(source: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(source).map(Self)
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Theme> {
+        self.0.iter().find(|theme| theme.name == name)
+    }
+
+    /// 列表中排在 `current` 后面的主题，到末尾后回到第一个
+    pub fn next_after(&self, current: &str) -> &Theme {
+        let index = self.0.iter().position(|theme| theme.name == current);
+        &self.0[index.map_or(0, |i| (i + 1) % self.0.len())]
+    }
+}
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        let registry = ThemeRegistry::from_ron(include_str!("../assets/themes.ron"))
+            .expect("assets/themes.ron 格式错误");
+        // 优先使用设置中选择的主题
+        let chosen = app
+            .world()
+            .get_resource::<Settings>()
+            .and_then(|settings| registry.find(&settings.theme));
+        let theme = chosen
+            .or(registry.0.first())
+            .expect("至少需要一个主题")
+            .clone();
+        app.insert_resource(registry).insert_resource(theme);
+    }
+}
@@ -0,0 +1,110 @@
+//! 固定步长的模拟：规则相关的系统都在 `FixedUpdate` 中运行，每运行一次是一个节拍，蛇在每个节拍走一格。
+//! 一帧可能模拟零个或多个节拍，卡顿时补上落下的节拍，同样的输入得到同样的一局；画面在两个节拍之间插值。
+//! 一个节拍内的规则按 `GameSet` 的顺序运行
+
+use std::time::Duration;
+
+use bevy::app::RunFixedMainLoop;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::time::run_fixed_main_schedule;
+use serde::{Deserialize, Serialize};
+
+use crate::powerup::EffectModifiers;
+use crate::GameState;
+
+pub struct TickPlugin;
+
+/// 这一局已经模拟的节拍数，开始新的一局时归零
+#[derive(
+    Resource, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize,
+)]
+pub struct GameTick(pub u64);
+
+/// 规则系统的先后顺序：按键 → 移动 → 碰撞 → 进食 → 生长 → 生成。
+/// 按键在每帧的 `PreUpdate` 中处理，这一帧按下的方向在这一帧的节拍中就生效；其余的在 `FixedUpdate` 中，
+/// 只在游戏进行中运行
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameSet {
+    Input,
+    /// 蛇头走一格，撞到墙、障碍物、敌人或自己时这一拍不移动
+    Move,
+    /// 敌人移动、模式计时和缩圈，以及由此造成的碰撞
+    Collide,
+    /// 吃掉食物和道具，计分
+    Eat,
+    /// 按这一拍的进食改变长度
+    Grow,
+    /// 在空出来的格子上补食物和道具
+    Spawn,
+}
+
+/// 一个节拍的基础长度，也就是蛇走一格的时间，由设置中的速度决定
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TickInterval(pub Duration);
+
+impl Default for TickInterval {
+    fn default() -> Self {
+        Self(Duration::from_secs_f32(0.20))
+    }
+}
+
+impl TickInterval {
+    /// 道具改变速度时实际的节拍长度，速度减半时节拍长一倍
+    pub fn scaled(self, speed_scale: f32) -> Duration {
+        self.0.mul_f64(1.0 / f64::from(speed_scale))
+    }
+}
+
+impl Plugin for TickPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameTick>()
+            .init_resource::<TickInterval>()
+            .configure_sets(PreUpdate, GameSet::Input.after(InputSystem))
+            .configure_sets(
+                FixedUpdate,
+                (
+                    GameSet::Move,
+                    GameSet::Collide,
+                    GameSet::Eat,
+                    GameSet::Grow,
+                    GameSet::Spawn,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::Playing), reset_tick)
+            .add_systems(
+                FixedFirst,
+                advance_tick.run_if(in_state(GameState::Playing)),
+            )
+            // 设置在这一帧改变的速度从下一个节拍开始生效，道具改变的速度在同一帧的后续节拍中就生效
+            .add_systems(
+                RunFixedMainLoop,
+                apply_timestep.before(run_fixed_main_schedule),
+            )
+            .add_systems(FixedLast, apply_timestep);
+    }
+}
+
+// 第一步在开局后整整一个节拍才走
+pub fn reset_tick(mut tick: ResMut<GameTick>, mut time: ResMut<Time<Fixed>>) {
+    *tick = GameTick::default();
+    let overstep = time.overstep();
+    time.discard_overstep(overstep);
+}
+
+fn advance_tick(mut tick: ResMut<GameTick>) {
+    tick.0 += 1;
+}
+
+fn apply_timestep(
+    interval: Res<TickInterval>,
+    modifiers: Res<EffectModifiers>,
+    mut time: ResMut<Time<Fixed>>,
+) {
+    let timestep = interval.scaled(modifiers.speed_scale);
+    if time.timestep() != timestep {
+        time.set_timestep(timestep);
+    }
+}
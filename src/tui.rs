@@ -0,0 +1,482 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use crossterm::event::{self, Event, KeyCode as TermKey, KeyEventKind, KeyModifiers};
+use crossterm::style::{self, Stylize};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::actions::control::{GameControl, KeyBindings};
+use crate::actions::Position;
+use crate::bounds::Bounds;
+use crate::enemy::{Enemy, EnemyKind};
+use crate::food::{Food, FoodKind, FoodLifetime};
+use crate::gameover::LastGameOver;
+use crate::i18n::Localizer;
+use crate::mode::{GameMode, ModeClock};
+use crate::obstacle::Obstacle;
+use crate::portal::Portal;
+use crate::powerup::{ActiveEffects, PowerUp, PowerUpRegistry};
+use crate::score::{Combo, Score};
+use crate::settings::Settings;
+use crate::snake::{snake_movment_input, SnakeBlock, SnakeHead};
+use crate::theme::{Theme, ThemePlugin};
+use crate::tick::GameSet;
+use crate::{Arena, GameState};
+
+/// 终端前端：用字符绘制棋盘，从终端读取按键，需要和 `HeadlessPlugin` 一起使用
+pub struct TuiPlugin;
+
+impl Plugin for TuiPlugin {
+    fn build(&self, app: &mut App) {
+        // 和窗口版本一样，界面语言取自设置
+        let language = app
+            .world()
+            .get_resource::<Settings>()
+            .map(|settings| settings.language)
+            .unwrap_or_default();
+        app.insert_resource(Localizer::new(language));
+
+        app.add_plugins(ThemePlugin)
+            .init_resource::<Glyphs>()
+            .init_resource::<TuiScreen>()
+            .add_systems(
+                PreUpdate,
+                read_terminal_input
+                    .in_set(GameSet::Input)
+                    .before(snake_movment_input),
+            )
+            .add_systems(Last, draw_screen);
+    }
+}
+
+/// 绘制用的字符，每个格子占两列，这样格子在终端里接近正方形
+#[derive(Resource, Clone, Copy)]
+pub struct Glyphs {
+    pub head: &'static str,
+    pub body: &'static str,
+    pub food: &'static str,
+    pub power_up: &'static str,
+    pub obstacle: &'static str,
+    pub danger: &'static str,
+    pub portal: &'static str,
+    /// 巡逻块、游荡者、追击者
+    pub enemies: [&'static str; 3],
+    pub empty: &'static str,
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+    pub corners: [&'static str; 4],
+}
+
+impl Glyphs {
+    pub const UNICODE: Self = Self {
+        head: "██",
+        body: "▓▓",
+        food: "●●",
+        power_up: "◆◆",
+        obstacle: "▒▒",
+        danger: "░░",
+        portal: "◎◎",
+        enemies: ["■■", "¤¤", "▲▲"],
+        empty: "  ",
+        horizontal: "─",
+        vertical: "│",
+        corners: ["┌", "┐", "└", "┘"],
+    };
+
+    pub const ASCII: Self = Self {
+        head: "@@",
+        body: "[]",
+        food: "<>",
+        power_up: "$$",
+        obstacle: "##",
+        danger: "::",
+        portal: "()",
+        enemies: ["==", "%%", "!!"],
+        empty: "  ",
+        horizontal: "-",
+        vertical: "|",
+        corners: ["+", "+", "+", "+"],
+    };
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self::UNICODE
+    }
+}
+
+/// 上一次输出到终端的画面，画面不变时不重绘
+#[derive(Resource, Default)]
+struct TuiScreen {
+    last_frame: String,
+    final_score: u32,
+}
+
+/// 进入终端的原始模式和备用屏幕，drop 时恢复
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(
+            io::stdout(),
+            terminal::EnterAlternateScreen,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::Hide
+        )?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// 终端只有按下事件，所以每帧先松开所有按键，再按下这一帧读到的键；
+// 设置里绑定到方向的键优先于 q、m 等终端版本自己的按键
+fn read_terminal_input(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut mode: ResMut<GameMode>,
+    mut exit: EventWriter<AppExit>,
+) {
+    keys.release_all();
+    keys.clear();
+
+    while let Ok(true) = event::poll(Duration::ZERO) {
+        let Ok(Event::Key(key)) = event::read() else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        let code = map_key(key.code);
+        // Ctrl+C 总是退出，即使 C 被绑定到某个方向
+        let bound = code.filter(|&code| {
+            !key.modifiers.contains(KeyModifiers::CONTROL)
+                && GameControl::ALL
+                    .iter()
+                    .any(|&control| bindings.keys(control).contains(&code))
+        });
+        if let Some(code) = bound {
+            keys.press(code);
+            continue;
+        }
+        match key.code {
+            TermKey::Esc | TermKey::Char('q') => {
+                exit.send(AppExit::Success);
+            }
+            TermKey::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                exit.send(AppExit::Success);
+            }
+            TermKey::Enter | TermKey::Char(' ') => {
+                if *state.get() != GameState::Playing {
+                    next_state.set(GameState::Playing);
+                }
+            }
+            // 在菜单和结束画面切换下一局的模式
+            TermKey::Char('m') if *state.get() != GameState::Playing => {
+                let index = GameMode::ALL.iter().position(|&other| other == *mode);
+                *mode = GameMode::ALL[index.map_or(0, |index| (index + 1) % GameMode::ALL.len())];
+            }
+            _ => {
+                if let Some(code) = code {
+                    keys.press(code);
+                }
+            }
+        }
+    }
+}
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::KeyA,
+    KeyCode::KeyB,
+    KeyCode::KeyC,
+    KeyCode::KeyD,
+    KeyCode::KeyE,
+    KeyCode::KeyF,
+    KeyCode::KeyG,
+    KeyCode::KeyH,
+    KeyCode::KeyI,
+    KeyCode::KeyJ,
+    KeyCode::KeyK,
+    KeyCode::KeyL,
+    KeyCode::KeyM,
+    KeyCode::KeyN,
+    KeyCode::KeyO,
+    KeyCode::KeyP,
+    KeyCode::KeyQ,
+    KeyCode::KeyR,
+    KeyCode::KeyS,
+    KeyCode::KeyT,
+    KeyCode::KeyU,
+    KeyCode::KeyV,
+    KeyCode::KeyW,
+    KeyCode::KeyX,
+    KeyCode::KeyY,
+    KeyCode::KeyZ,
+];
+
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// 把终端按键换成 bevy 的按键，终端读不出的键（如小键盘）返回 `None`
+pub fn map_key(code: TermKey) -> Option<KeyCode> {
+    match code {
+        TermKey::Up => Some(KeyCode::ArrowUp),
+        TermKey::Down => Some(KeyCode::ArrowDown),
+        TermKey::Left => Some(KeyCode::ArrowLeft),
+        TermKey::Right => Some(KeyCode::ArrowRight),
+        TermKey::Char(c) => match c.to_ascii_lowercase() {
+            c @ 'a'..='z' => Some(LETTERS[(c as u8 - b'a') as usize]),
+            c @ '0'..='9' => Some(DIGITS[(c as u8 - b'0') as usize]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Empty,
+    Head,
+    Body,
+    /// 第二个值表示正在淡出
+    Food(FoodKind, bool),
+    PowerUp(usize),
+    Obstacle,
+    /// 活动范围外的危险区
+    Danger,
+    Portal,
+    /// 值为 `Glyphs::enemies` 的下标
+    Enemy(usize),
+}
+
+fn draw_screen(
+    mut screen: ResMut<TuiScreen>,
+    glyphs: Res<Glyphs>,
+    (state, last): (Res<State<GameState>>, Res<LastGameOver>),
+    score: Res<Score>,
+    combo: Res<Combo>,
+    arena: Res<Arena>,
+    bounds: Res<Bounds>,
+    // 系统参数最多 16 个，模式和计时放在一起
+    (mode, clock): (Res<GameMode>, Res<ModeClock>),
+    (theme, localizer): (Res<Theme>, Res<Localizer>),
+    registry: Res<PowerUpRegistry>,
+    effects: Res<ActiveEffects>,
+    heads: Query<&Position, With<SnakeHead>>,
+    blocks: Query<&Position, (With<SnakeBlock>, Without<SnakeHead>)>,
+    food: Query<(&Position, &Food, Option<&FoodLifetime>)>,
+    power_ups: Query<(&Position, &PowerUp)>,
+    // 障碍物、传送门和敌人
+    fixtures: Query<
+        (&Position, Has<Portal>, Option<&Enemy>),
+        Or<(With<Obstacle>, With<Portal>, With<Enemy>)>,
+    >,
+) {
+    let (width, height) = (arena.width as usize, arena.height as usize);
+    let mut cells = vec![Cell::Empty; width * height];
+    arena
+        .cells()
+        .filter(|pos| !bounds.contains(*pos))
+        .for_each(|pos| {
+            cells[(height - 1 - pos.y as usize) * width + pos.x as usize] = Cell::Danger;
+        });
+    let mut put = |pos: &Position, cell: Cell| {
+        if arena.contains(*pos) {
+            // 终端的行从上往下数，棋盘的 y 从下往上数
+            cells[(height - 1 - pos.y as usize) * width + pos.x as usize] = cell;
+        }
+    };
+    for (pos, food, lifetime) in food.iter() {
+        let (visible, fading) = match lifetime {
+            Some(lifetime) => (
+                !food.0.blinks() || lifetime.blink_visible(),
+                lifetime.fade() < 1.0,
+            ),
+            None => (true, false),
+        };
+        if visible {
+            put(pos, Cell::Food(food.0, fading));
+        }
+    }
+    power_ups
+        .iter()
+        .for_each(|(pos, power_up)| put(pos, Cell::PowerUp(power_up.0)));
+    for (pos, portal, enemy) in fixtures.iter() {
+        let cell = match enemy.map(|enemy| &enemy.kind) {
+            Some(EnemyKind::Patrol { .. }) => Cell::Enemy(0),
+            Some(EnemyKind::Wanderer) => Cell::Enemy(1),
+            Some(EnemyKind::Chaser) => Cell::Enemy(2),
+            None if portal => Cell::Portal,
+            None => Cell::Obstacle,
+        };
+        put(pos, cell);
+    }
+    blocks.iter().for_each(|pos| put(pos, Cell::Body));
+    heads.iter().for_each(|pos| put(pos, Cell::Head));
+
+    // 进入 GameOver 时分数会被清零，这里记住最后一局的分数
+    if *state.get() == GameState::Playing {
+        screen.final_score = score.get();
+    }
+    let (title, hint) = match state.get() {
+        GameState::Menu => (localizer.get("window-title"), "tui-hint-menu"),
+        GameState::Playing => (String::new(), "tui-hint-playing"),
+        GameState::GameOver => (localizer.get("game-over"), "tui-hint-game-over"),
+    };
+
+    let mut frame = String::new();
+    let _ = write!(
+        frame,
+        "{}{}{}{}",
+        localizer.get("score-label"),
+        screen.final_score,
+        localizer.get("streak-label"),
+        combo.streak
+    );
+    match *mode {
+        GameMode::Classic => {}
+        GameMode::TimeAttack(_) => {
+            let remaining = clock.remaining(*mode).unwrap_or_default();
+            // 和窗口版本一样向上取整
+            let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+            let _ = write!(
+                frame,
+                "   {}",
+                localizer.get_with("hud-time-left", [("secs", secs.into())])
+            );
+        }
+        GameMode::Survival => {
+            let secs = clock.elapsed.as_secs();
+            let _ = write!(
+                frame,
+                "   {}",
+                localizer.get_with("hud-survived", [("secs", secs.into())])
+            );
+        }
+    }
+    for active in &effects.0 {
+        let def = &registry.0[active.def];
+        let name = localizer
+            .try_get(&format!("powerup-{}", def.name))
+            .unwrap_or_else(|| def.name.clone());
+        let _ = write!(
+            frame,
+            "   {}",
+            format!("{} {:.1}s", name, active.timer.remaining_secs()).with(term_color(def.color()))
+        );
+    }
+    frame.push('\n');
+    let border = glyphs.horizontal.repeat(width * 2);
+    let _ = writeln!(
+        frame,
+        "{}{}{}",
+        glyphs.corners[0], border, glyphs.corners[1]
+    );
+    for row in cells.chunks(width) {
+        frame.push_str(glyphs.vertical);
+        for cell in row {
+            let _ = match cell {
+                Cell::Empty => write!(frame, "{}", glyphs.empty),
+                Cell::Head => write!(
+                    frame,
+                    "{}",
+                    glyphs.head.with(term_color(theme.snake_head.0))
+                ),
+                Cell::Body => write!(
+                    frame,
+                    "{}",
+                    glyphs.body.with(term_color(theme.snake_body.0))
+                ),
+                Cell::Food(kind, false) => {
+                    write!(frame, "{}", glyphs.food.with(term_color(theme.food(*kind))))
+                }
+                Cell::Food(kind, true) => {
+                    write!(
+                        frame,
+                        "{}",
+                        glyphs.food.with(term_color(theme.food(*kind))).dim()
+                    )
+                }
+                Cell::PowerUp(index) => write!(
+                    frame,
+                    "{}",
+                    glyphs.power_up.with(term_color(registry.0[*index].color()))
+                ),
+                Cell::Danger => write!(frame, "{}", glyphs.danger.with(term_color(theme.danger.0))),
+                Cell::Portal => write!(frame, "{}", glyphs.portal.with(term_color(theme.portal.0))),
+                Cell::Enemy(index) => write!(
+                    frame,
+                    "{}",
+                    glyphs.enemies[*index].with(term_color(theme.enemy.0))
+                ),
+                Cell::Obstacle => write!(
+                    frame,
+                    "{}",
+                    glyphs.obstacle.with(term_color(theme.obstacle.0))
+                ),
+            };
+        }
+        frame.push_str(glyphs.vertical);
+        frame.push('\n');
+    }
+    let _ = writeln!(
+        frame,
+        "{}{}{}",
+        glyphs.corners[2], border, glyphs.corners[3]
+    );
+    if !title.is_empty() {
+        let _ = write!(frame, "{}   ", title.bold());
+    }
+    if let (GameState::GameOver, Some(event)) = (state.get(), last.0) {
+        let _ = write!(frame, "{}   ", localizer.get(&event.cause.key()));
+    }
+    if *state.get() != GameState::Playing {
+        let _ = write!(
+            frame,
+            "{}   ",
+            localizer.get_with("tui-mode", [("mode", mode.label(&localizer).into())])
+        );
+    }
+    frame.push_str(&localizer.get(hint));
+
+    if frame == screen.last_frame {
+        return;
+    }
+    let mut stdout = io::stdout().lock();
+    for (row, line) in frame.lines().enumerate() {
+        let _ = queue!(
+            stdout,
+            cursor::MoveTo(0, row as u16),
+            style::Print(line),
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        );
+    }
+    let _ = stdout.flush();
+    screen.last_frame = frame;
+}
+
+fn term_color(color: Color) -> style::Color {
+    let [r, g, b, _] = color.to_srgba().to_u8_array();
+    style::Color::Rgb { r, g, b }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use bevy::utils::HashSet;
+use common::TestApp;
+use snake::actions::Position;
+use snake::bounds::{Bounds, ShrinkSchedule};
+use snake::food::random_free_cell;
+use snake::mode::GameMode;
+use snake::{Arena, GameRng, GameState};
+
+fn schedule(game: &mut TestApp, every: f32) {
+    game.app
+        .insert_resource(ShrinkSchedule { every, min_size: 7 });
+}
+
+#[test]
+fn survival_shrinks_one_ring_per_interval() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    let full = game.bounds();
+    assert_eq!(full, Bounds::full(&Arena::default()));
+    game.fast_forward(30.0);
+    assert_eq!(game.state(), GameState::Playing);
+    assert_eq!(game.bounds(), full.shrink(1));
+}
+
+#[test]
+fn shrinking_stops_at_minimum_size() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    schedule(&mut game, 0.001);
+    game.fast_forward(1.0);
+    let bounds = game.bounds();
+    assert_eq!((bounds.width(), bounds.height()), (7, 7));
+}
+
+#[test]
+fn other_modes_keep_full_arena() {
+    let mut game = TestApp::playing_mode(GameMode::Classic);
+    schedule(&mut game, 1.0);
+    game.fast_forward(10.0);
+    assert_eq!(game.bounds(), Bounds::full(&Arena::default()));
+}
+
+#[test]
+fn head_caught_by_the_border_dies() {
+    // 蛇头在 (3, 3)，缩四圈后在圈外
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    schedule(&mut game, 1.0);
+    game.fast_forward(4.0);
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn food_outside_is_cleared_and_never_spawned() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    game.spawn_food(Position { x: 0, y: 20 });
+    schedule(&mut game, 30.0);
+    game.fast_forward(30.0);
+    let bounds = game.bounds();
+    assert!(game.food().iter().all(|pos| bounds.contains(*pos)));
+
+    let mut rng = GameRng::seeded(1);
+    let occupied = HashSet::new();
+    for _ in 0..200 {
+        let pos = random_free_cell(&mut rng, &bounds, &occupied).unwrap();
+        assert!(bounds.contains(pos));
+    }
+}
+
+#[test]
+fn zero_minimum_size_still_leaves_a_cell() {
+    for arena in [
+        Arena::default(),
+        Arena {
+            width: 24,
+            height: 24,
+        },
+    ] {
+        let schedule = ShrinkSchedule {
+            every: 1.0,
+            min_size: 0,
+        };
+        let rings = schedule.rings(std::time::Duration::from_secs(100), &arena);
+        let bounds = Bounds::full(&arena).shrink(rings);
+        assert!(bounds.width() >= 1 && bounds.height() >= 1, "{arena:?}");
+        assert!(bounds.contains(bounds.wrap(Position { x: 0, y: 0 })));
+    }
+}
+
+#[test]
+fn wrapping_stays_inside_bounds() {
+    let bounds = Bounds::full(&Arena::default()).shrink(2);
+    assert_eq!(
+        bounds.wrap(Position { x: 1, y: 10 }),
+        Position { x: 22, y: 10 }
+    );
+    assert_eq!(
+        bounds.wrap(Position { x: 10, y: 23 }),
+        Position { x: 10, y: 2 }
+    );
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{at, TestApp};
+use snake::actions::control::GameControl;
+use snake::actions::Position;
+use snake::food::FoodKind;
+use snake::gameover::GameOverCause;
+use snake::powerup::{ActiveEffects, PowerUpRegistry};
+use snake::snake::{CollisionPolicy, PendingGrowth};
+
+// 蛇头向左走进尾巴所在的 (4, 5)
+const RING: [Position; 4] = [at(5, 5), at(5, 6), at(4, 6), at(4, 5)];
+
+fn ring(policy: CollisionPolicy) -> TestApp {
+    let mut game = TestApp::playing();
+    game.configure(|settings| settings.collision = policy);
+    game.replace_snake(&RING, GameControl::Left);
+    game
+}
+
+#[test]
+fn blocked_cells_follow_the_policy() {
+    let tail = &RING[..3];
+    assert_eq!(CollisionPolicy::TailVacates.blocked(&RING, false), tail);
+    assert_eq!(CollisionPolicy::TailVacates.blocked(&RING, true), RING);
+    assert_eq!(CollisionPolicy::Strict.blocked(&RING, false), RING);
+    assert_eq!(CollisionPolicy::Strict.blocked(&RING, true), RING);
+}
+
+#[test]
+fn head_follows_the_tail_when_it_vacates() {
+    let mut game = ring(CollisionPolicy::TailVacates);
+    game.tick();
+    assert_eq!(game.game_over().map(|event| event.cause), None);
+    assert_eq!(game.segments(), [at(4, 5), at(5, 5), at(5, 6), at(4, 6)]);
+}
+
+#[test]
+fn strict_policy_blocks_the_tail() {
+    let mut game = ring(CollisionPolicy::Strict);
+    game.tick();
+    assert_eq!(
+        game.game_over().map(|event| event.cause),
+        Some(GameOverCause::Body)
+    );
+    assert_eq!(game.segments(), RING);
+}
+
+#[test]
+fn tail_stays_when_growing_on_the_same_tick() {
+    let mut game = ring(CollisionPolicy::TailVacates);
+    game.spawn_food(at(4, 5));
+    game.tick();
+    assert_eq!(
+        game.game_over().map(|event| event.cause),
+        Some(GameOverCause::Body)
+    );
+    assert_eq!(game.segments(), RING);
+}
+
+#[test]
+fn shrinking_food_does_not_hold_the_tail() {
+    let mut game = ring(CollisionPolicy::TailVacates);
+    game.spawn_food_of(FoodKind::Poison, at(4, 5));
+    game.tick();
+    assert_eq!(game.game_over().map(|event| event.cause), None);
+    assert_eq!(game.segments(), [at(4, 5), at(5, 5), at(5, 6)]);
+}
+
+#[test]
+fn magnet_does_not_pull_food_onto_the_head() {
+    let mut game = ring(CollisionPolicy::TailVacates);
+    let world = game.app.world_mut();
+    let registry = world.resource::<PowerUpRegistry>();
+    let magnet = registry.find("Magnet").unwrap();
+    let def = registry.0[magnet].clone();
+    world.resource_mut::<ActiveEffects>().activate(magnet, &def);
+    game.spawn_food(at(3, 5));
+    game.tick();
+    assert_eq!(game.game_over().map(|event| event.cause), None);
+    assert_eq!(game.segments(), [at(4, 5), at(5, 5), at(5, 6), at(4, 6)]);
+    assert!(game.food().contains(&at(3, 5)), "食物停在蛇头旁边");
+}
+
+#[test]
+fn shrinking_on_the_tail_cell_outweighs_queued_growth() {
+    let mut game = ring(CollisionPolicy::TailVacates);
+    game.app.world_mut().insert_resource(PendingGrowth(1));
+    game.spawn_power_up("Shrink", at(4, 5));
+    game.tick();
+    assert_eq!(game.game_over().map(|event| event.cause), None);
+    assert_eq!(game.segments(), [at(4, 5), at(5, 5)]);
+}
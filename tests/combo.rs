@@ -0,0 +1,89 @@
+mod common;
+
+use common::TestApp;
+use snake::actions::Position;
+use snake::food::FoodKind;
+use snake::score::{Combo, ComboRules, ScoreLog, ScoreSource};
+use snake::GameState;
+
+#[test]
+fn quick_eats_raise_the_multiplier() {
+    let mut game = TestApp::playing();
+    for y in 4..7 {
+        game.spawn_food(Position { x: 3, y });
+    }
+    game.advance_ticks(3);
+    // 1 + 1x2 + 1x3
+    assert_eq!(game.score(), 6);
+    assert_eq!(game.app.world().resource::<Combo>().streak, 3);
+}
+
+#[test]
+fn streak_breaks_after_the_window() {
+    let mut game = TestApp::playing();
+    game.app.insert_resource(ComboRules {
+        window: 2,
+        ..Default::default()
+    });
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.spawn_food(Position { x: 3, y: 8 });
+    game.advance_ticks(2);
+    assert_eq!(game.app.world().resource::<Combo>().streak, 1);
+    game.advance_ticks(2);
+    assert_eq!(game.app.world().resource::<Combo>().streak, 0);
+    game.tick();
+    assert_eq!(game.score(), 2);
+    assert_eq!(game.app.world().resource::<Combo>().streak, 1);
+}
+
+#[test]
+fn multiplier_is_capped() {
+    let mut game = TestApp::playing();
+    game.app.insert_resource(ComboRules {
+        window: 10,
+        step: 2,
+        max_multiplier: 2,
+    });
+    for y in 4..9 {
+        game.spawn_food(Position { x: 3, y });
+    }
+    game.advance_ticks(5);
+    // 倍率依次为 1 1 2 2 2
+    assert_eq!(game.score(), 8);
+}
+
+#[test]
+fn poison_breaks_the_streak() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.spawn_food_of(FoodKind::Poison, Position { x: 3, y: 5 });
+    game.spawn_food(Position { x: 3, y: 6 });
+    game.advance_ticks(3);
+    assert_eq!(game.score(), 1);
+    assert_eq!(game.app.world().resource::<Combo>().streak, 1);
+}
+
+#[test]
+fn breakdown_survives_game_over() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.spawn_food_of(FoodKind::Golden, Position { x: 3, y: 5 });
+    game.spawn_food(Position { x: 3, y: 6 });
+    game.advance_ticks(3);
+    game.set_state(GameState::GameOver);
+    game.update();
+
+    let log = game.app.world().resource::<ScoreLog>();
+    assert_eq!(
+        log.by_source(),
+        vec![
+            (ScoreSource::Food(FoodKind::Normal), 2, 4),
+            (ScoreSource::Food(FoodKind::Golden), 1, 10)
+        ]
+    );
+    assert_eq!(game.app.world().resource::<Combo>().best, 3);
+
+    game.set_state(GameState::Playing);
+    game.update();
+    assert!(game.app.world().resource::<ScoreLog>().0.is_empty());
+}
@@ -0,0 +1,216 @@
+//! 集成测试用的 `App`：只包含游戏规则，可以模拟按键、按节拍推进时间并检查游戏状态
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use snake::actions::control::GameControl;
+use snake::actions::Position;
+use snake::bounds::Bounds;
+use snake::food::{spawn_food_at, Food, FoodConfig, FoodKind};
+use snake::gameover::{GameOverEvent, LastGameOver};
+use snake::mode::{GameMode, ModeClock};
+use snake::powerup::{PowerUp, PowerUpRegistry};
+use snake::score::Score;
+use snake::settings::Settings;
+use snake::snake::{spawn_snake_at, SnakeBlocks, SnakeHead};
+use snake::tick::TickInterval;
+use snake::{GameRng, GameState, LogicPlugin};
+
+/// 格子坐标的简写
+pub const fn at(x: i32, y: i32) -> Position {
+    Position { x, y }
+}
+
+pub struct TestApp {
+    pub app: App,
+}
+
+impl TestApp {
+    /// 创建停在主菜单的游戏，时间只在 `tick` 时推进
+    pub fn new() -> Self {
+        Self::seeded(0)
+    }
+
+    /// 用指定的随机数种子创建游戏；放宽 `Time<Virtual>` 的单帧上限，慢速的节拍也能一帧走完
+    pub fn seeded(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, LogicPlugin))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .insert_resource(GameRng::seeded(seed))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        app.world_mut()
+            .resource_mut::<Time<Virtual>>()
+            .set_max_delta(Duration::from_secs(3600));
+        app.update();
+        Self { app }
+    }
+
+    /// 创建已经开始的游戏
+    pub fn playing() -> Self {
+        let mut test = Self::new();
+        test.set_state(GameState::Playing);
+        test
+    }
+
+    /// 创建已经开始的某种模式的游戏
+    pub fn playing_mode(mode: GameMode) -> Self {
+        let mut test = Self::new();
+        test.app.insert_resource(mode);
+        test.set_state(GameState::Playing);
+        test
+    }
+
+    pub fn set_state(&mut self, state: GameState) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+        self.app.update();
+    }
+
+    /// 修改设置，跑一帧让设置生效
+    pub fn configure(&mut self, change: impl FnOnce(&mut Settings)) {
+        change(&mut self.app.world_mut().resource_mut::<Settings>());
+        self.app.update();
+    }
+
+    /// 按下一个键，下一次 `tick` 之后松开
+    pub fn press(&mut self, key: KeyCode) {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+    }
+
+    /// 推进一个基础节拍的时间，减速道具生效时蛇不一定走
+    pub fn tick(&mut self) {
+        let step = self.app.world().resource::<TickInterval>().0;
+        self.frame(step);
+    }
+
+    /// 跑经过 `duration` 的一帧，这段时间内到期的节拍都会模拟
+    pub fn frame(&mut self, duration: Duration) {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+        self.app.update();
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+
+        let mut keys = self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release_all();
+        keys.clear();
+    }
+
+    /// 快进 `secs` 秒：这一局的计时直接跳到最后一个节拍之前，只模拟最后一个节拍，蛇只走一步
+    pub fn fast_forward(&mut self, secs: f32) {
+        let step = self.app.world().resource::<TickInterval>().0;
+        self.app.world_mut().resource_mut::<ModeClock>().elapsed +=
+            Duration::from_secs_f32(secs) - step;
+        self.tick();
+        self.app.update();
+    }
+
+    pub fn advance_ticks(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.tick();
+        }
+    }
+
+    /// 不推进时间，只跑一帧，用于处理上一帧发出的事件和命令
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app
+            .world()
+            .resource::<State<GameState>>()
+            .get()
+            .clone()
+    }
+
+    /// 已经设置、下一帧才会生效的状态
+    pub fn pending_state(&self) -> Option<GameState> {
+        match self.app.world().resource::<NextState<GameState>>() {
+            NextState::Pending(state) => Some(state.clone()),
+            NextState::Unchanged => None,
+        }
+    }
+
+    /// 这一局结束的原因，还没结束时为 `None`
+    pub fn game_over(&self) -> Option<GameOverEvent> {
+        self.app.world().resource::<LastGameOver>().0
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        *self.app.world().resource::<Bounds>()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.app.world().resource::<Score>().get()
+    }
+
+    pub fn head(&mut self) -> Position {
+        *self
+            .app
+            .world_mut()
+            .query_filtered::<&Position, With<SnakeHead>>()
+            .single(self.app.world())
+    }
+
+    /// 蛇的每一节，从头到尾，已经被清理的不算在内
+    pub fn segments(&self) -> Vec<Position> {
+        let world = self.app.world();
+        world
+            .resource::<SnakeBlocks>()
+            .iter()
+            .filter_map(|entity| world.get::<Position>(*entity).copied())
+            .collect()
+    }
+
+    /// 换成一条指定形状的蛇，`segments` 从头到尾
+    pub fn replace_snake(&mut self, segments: &[Position], direction: GameControl) {
+        let world = self.app.world_mut();
+        for entity in world.resource::<SnakeBlocks>().0.clone() {
+            world.despawn(entity);
+        }
+        let blocks = spawn_snake_at(&mut world.commands(), segments, direction);
+        world.flush();
+        world.insert_resource(blocks);
+    }
+
+    pub fn food(&mut self) -> Vec<Position> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Position, With<Food>>()
+            .iter(self.app.world())
+            .copied()
+            .collect()
+    }
+
+    pub fn spawn_food(&mut self, pos: Position) -> Entity {
+        self.spawn_food_of(FoodKind::Normal, pos)
+    }
+
+    pub fn spawn_food_of(&mut self, kind: FoodKind, pos: Position) -> Entity {
+        let world = self.app.world_mut();
+        let lifetime = world.resource::<FoodConfig>().lifetime_of(kind);
+        let entity = spawn_food_at(&mut world.commands(), kind, pos, lifetime);
+        world.flush();
+        entity
+    }
+
+    /// 按名字生成 `assets/powerups.ron` 中的道具
+    pub fn spawn_power_up(&mut self, name: &str, pos: Position) -> Entity {
+        let index = self
+            .app
+            .world()
+            .resource::<PowerUpRegistry>()
+            .find(name)
+            .unwrap();
+        self.app.world_mut().spawn((PowerUp(index), pos)).id()
+    }
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{at, TestApp};
+use snake::actions::Position;
+use snake::enemy::{chase_step, Enemy, EnemyConfig, EnemyKind, RANDOM_ENEMIES};
+use snake::obstacle::Obstacle;
+use snake::save::{PendingRestore, SaveGame};
+use snake::GameState;
+
+fn playing_with(config: EnemyConfig, seed: u64) -> TestApp {
+    let mut game = TestApp::seeded(seed);
+    game.app.insert_resource(config);
+    game.set_state(GameState::Playing);
+    game
+}
+
+fn enemies(game: &mut TestApp) -> Vec<(Position, Enemy)> {
+    let mut enemies = game
+        .app
+        .world_mut()
+        .query::<(&Position, &Enemy)>()
+        .iter(game.app.world())
+        .map(|(&pos, enemy)| (pos, enemy.clone()))
+        .collect::<Vec<_>>();
+    enemies.sort_by_key(|(pos, _)| (pos.x, pos.y));
+    enemies
+}
+
+fn positions(game: &mut TestApp) -> Vec<Position> {
+    enemies(game).into_iter().map(|(pos, _)| pos).collect()
+}
+
+fn patrol(path: Vec<Position>) -> EnemyConfig {
+    EnemyConfig {
+        patrols: vec![path],
+        ..default()
+    }
+}
+
+#[test]
+fn patrol_walks_back_and_forth() {
+    let mut game = playing_with(patrol(vec![at(10, 10), at(11, 10), at(12, 10)]), 0);
+    let mut visited = Vec::new();
+    for _ in 0..7 {
+        game.tick();
+        visited.extend(positions(&mut game));
+    }
+    // 先等一轮，走到头时掉头也用掉一步
+    assert_eq!(
+        visited,
+        [
+            at(10, 10),
+            at(11, 10),
+            at(12, 10),
+            at(12, 10),
+            at(11, 10),
+            at(10, 10),
+            at(10, 10),
+        ]
+    );
+}
+
+#[test]
+fn head_running_into_enemy_ends_game() {
+    // 蛇头在 (3, 3) 向上走
+    let mut game = playing_with(patrol(vec![at(3, 6)]), 0);
+    game.advance_ticks(2);
+    assert_eq!(game.state(), GameState::Playing);
+    game.tick();
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn chaser_catches_the_head() {
+    let mut game = TestApp::playing();
+    game.app
+        .world_mut()
+        .spawn((Enemy::new(EnemyKind::Chaser), at(4, 6)));
+    game.advance_ticks(2);
+    assert_eq!(game.state(), GameState::Playing);
+    // 蛇头走到 (3, 6) 之后追击者从旁边扑上来
+    game.tick();
+    assert_eq!(positions(&mut game), [at(3, 6)]);
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn chase_step_goes_around_walls() {
+    // x = 5 的一列是墙，只在 y = 0 留了缺口
+    let open = |pos: Position| {
+        (0..10).contains(&pos.x) && (0..10).contains(&pos.y) && (pos.x != 5 || pos.y == 0)
+    };
+    assert_eq!(chase_step(at(4, 3), at(6, 3), open), Some(at(4, 2)));
+    assert_eq!(chase_step(at(4, 0), at(6, 0), open), Some(at(5, 0)));
+    let walled = |pos: Position| open(pos) && pos.x != 5;
+    assert_eq!(chase_step(at(4, 3), at(6, 3), walled), None);
+}
+
+#[test]
+fn wanderer_eats_food() {
+    let mut game = TestApp::playing();
+    let world = game.app.world_mut();
+    world.spawn((Enemy::new(EnemyKind::Wanderer), at(15, 15)));
+    for pos in [at(14, 15), at(16, 15), at(15, 14)] {
+        world.spawn((Obstacle, pos));
+    }
+    game.spawn_food(at(15, 16));
+    game.advance_ticks(3);
+    assert_eq!(positions(&mut game), [at(15, 16)]);
+    assert!(!game.food().contains(&at(15, 16)));
+    assert_eq!(game.score(), 0, "敌人吃掉的食物不计分");
+}
+
+#[test]
+fn random_enemies_follow_the_seed() {
+    let config = EnemyConfig {
+        random_patrols: 2,
+        wanderers: 2,
+        chasers: 1,
+        ..default()
+    };
+    let mut a = playing_with(config.clone(), 9);
+    let mut b = playing_with(config, 9);
+    assert_eq!(enemies(&mut a).len(), 5);
+    for _ in 0..6 {
+        assert_eq!(enemies(&mut a), enemies(&mut b));
+        a.tick();
+        b.tick();
+    }
+}
+
+#[test]
+fn enemies_survive_save_and_restore() {
+    let config = EnemyConfig {
+        patrols: vec![vec![at(10, 10), at(11, 10)]],
+        wanderers: 1,
+        chasers: 1,
+        ..default()
+    };
+    let mut original = playing_with(config, 3);
+    original.tick();
+    let save = SaveGame::capture(original.app.world_mut()).unwrap();
+    let mut restored = TestApp::seeded(4);
+    restored.app.insert_resource(PendingRestore(save));
+    restored.set_state(GameState::Playing);
+    assert_eq!(enemies(&mut restored), enemies(&mut original));
+}
+
+#[test]
+fn settings_turn_random_enemies_on() {
+    let mut game = TestApp::new();
+    game.configure(|settings| settings.enemies = true);
+    game.set_state(GameState::Playing);
+    assert_eq!(enemies(&mut game).len(), 3 * RANDOM_ENEMIES as usize);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::Position;
+use snake::food::{Food, FoodConfig, FoodKind, FoodSpawnMode};
+use snake::score::ComboRules;
+use snake::GameState;
+
+#[test]
+fn golden_food_is_worth_five_points() {
+    let mut game = TestApp::playing();
+    game.spawn_food_of(FoodKind::Golden, Position { x: 3, y: 4 });
+    game.tick();
+    assert_eq!(game.score(), 5);
+    assert_eq!(game.segments().len(), 3);
+}
+
+#[test]
+fn poison_costs_points_and_shrinks_the_snake() {
+    let mut game = TestApp::playing();
+    game.app.insert_resource(ComboRules::off());
+    for y in 4..7 {
+        game.spawn_food_of(FoodKind::Golden, Position { x: 3, y });
+    }
+    game.spawn_food_of(FoodKind::Poison, Position { x: 3, y: 7 });
+    game.advance_ticks(4);
+    game.update();
+    assert_eq!(game.score(), 13);
+    assert_eq!(game.segments().len(), 4);
+}
+
+#[test]
+fn poison_never_shrinks_below_two_segments() {
+    let mut game = TestApp::playing();
+    game.spawn_food_of(FoodKind::Poison, Position { x: 3, y: 4 });
+    game.tick();
+    game.update();
+    assert_eq!(game.score(), 0);
+    assert_eq!(game.segments().len(), 2);
+}
+
+#[test]
+fn rare_food_expires() {
+    let mut game = TestApp::playing();
+    game.spawn_food_of(FoodKind::Rare, Position { x: 20, y: 5 });
+    // 5 秒 = 25 个节拍，斜着走免得撞墙
+    for key in [KeyCode::ArrowRight, KeyCode::ArrowUp]
+        .iter()
+        .cycle()
+        .take(24)
+    {
+        game.press(*key);
+        game.tick();
+    }
+    assert!(game.food().contains(&Position { x: 20, y: 5 }));
+    game.tick();
+    game.update();
+    assert!(!game.food().contains(&Position { x: 20, y: 5 }));
+}
+
+#[test]
+fn classic_mode_respawns_in_the_same_tick() {
+    let mut game = TestApp::new();
+    game.app.insert_resource(FoodConfig {
+        mode: FoodSpawnMode::Classic,
+        ..default()
+    });
+    game.set_state(GameState::Playing);
+    // 第一个食物在第一个节拍生成
+    game.tick();
+    let spawned = game
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<Food>>()
+        .single(game.app.world());
+    game.app.world_mut().despawn(spawned);
+    game.spawn_food(Position { x: 3, y: 5 });
+
+    game.tick();
+    assert_eq!(game.score(), 1);
+    let food = game.food();
+    assert_eq!(food.len(), 1);
+    assert_ne!(food[0], Position { x: 3, y: 5 });
+}
+
+#[test]
+fn timer_mode_respects_the_food_cap() {
+    let mut game = TestApp::new();
+    game.app.insert_resource(FoodConfig {
+        max_food: 2,
+        ..default()
+    });
+    game.set_state(GameState::Playing);
+    for key in [KeyCode::ArrowRight, KeyCode::ArrowUp]
+        .iter()
+        .cycle()
+        .take(40)
+    {
+        game.press(*key);
+        game.tick();
+        assert!(game.food().len() <= 2);
+    }
+}
+
+#[test]
+fn food_expires_when_configured() {
+    let mut game = TestApp::new();
+    game.app.insert_resource(FoodConfig {
+        expiry: Some(1.0),
+        ..default()
+    });
+    game.set_state(GameState::Playing);
+    game.spawn_food(Position { x: 20, y: 5 });
+    game.advance_ticks(4);
+    assert!(game.food().contains(&Position { x: 20, y: 5 }));
+    game.tick();
+    game.update();
+    assert!(!game.food().contains(&Position { x: 20, y: 5 }));
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{at, TestApp};
+use snake::actions::control::GameControl;
+use snake::bounds::ShrinkSchedule;
+use snake::enemy::{Enemy, EnemyKind};
+use snake::gameover::{GameOverCause, GameOverEvent};
+use snake::mode::GameMode;
+use snake::obstacle::Obstacle;
+use snake::tick::{GameTick, TickInterval};
+use snake::GameState;
+
+#[test]
+fn wall_hit_stops_the_snake_on_the_fatal_tick() {
+    let mut game = TestApp::playing();
+    // 从 y = 3 走到 y = 24 还在场内
+    game.advance_ticks(21);
+    let before = game.segments();
+    game.tick();
+    assert_eq!(
+        game.game_over(),
+        Some(GameOverEvent {
+            cause: GameOverCause::Wall,
+            tick: 22,
+            position: at(3, 25),
+        })
+    );
+    assert_eq!(game.segments(), before, "结束的节拍不再移动");
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+    assert!(game.game_over().is_some(), "结束界面还能看到原因");
+}
+
+#[test]
+fn running_into_own_body() {
+    let mut game = TestApp::playing();
+    let body = [at(5, 5), at(5, 6), at(4, 6), at(4, 5), at(4, 4)];
+    game.replace_snake(&body, GameControl::Left);
+    game.tick();
+    let event = game.game_over().unwrap();
+    assert_eq!(
+        (event.cause, event.position),
+        (GameOverCause::Body, at(4, 5))
+    );
+    assert_eq!(game.segments(), body);
+}
+
+#[test]
+fn obstacles_and_enemies_are_told_apart() {
+    for (cause, blocker) in [
+        (GameOverCause::Obstacle, None),
+        (GameOverCause::Enemy, Some(EnemyKind::Wanderer)),
+    ] {
+        let mut game = TestApp::playing();
+        let world = game.app.world_mut();
+        match blocker {
+            Some(kind) => world.spawn((Enemy::new(kind), at(3, 4))),
+            None => world.spawn((Obstacle, at(3, 4))),
+        };
+        game.tick();
+        let event = game.game_over().unwrap();
+        assert_eq!(
+            (event.cause, event.tick, event.position),
+            (cause, 1, at(3, 4))
+        );
+        assert_eq!(game.head(), at(3, 3));
+    }
+}
+
+#[test]
+fn time_attack_runs_out() {
+    let mut game = TestApp::playing_mode(GameMode::TimeAttack(60));
+    game.fast_forward(60.0);
+    let event = game.game_over().unwrap();
+    assert_eq!(event.cause, GameOverCause::Timeout);
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn caught_by_the_shrinking_border() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    game.app.insert_resource(ShrinkSchedule {
+        every: 1.0,
+        min_size: 7,
+    });
+    game.fast_forward(4.0);
+    let event = game.game_over().unwrap();
+    // 蛇头走到 (3, 4) 时缩到了第四圈
+    assert_eq!(
+        (event.cause, event.position),
+        (GameOverCause::DangerZone, at(3, 4))
+    );
+}
+
+#[test]
+fn later_ticks_in_the_same_frame_are_dropped() {
+    let mut game = TestApp::playing();
+    game.app.world_mut().spawn((Obstacle, at(3, 5)));
+    let step = game.app.world().resource::<TickInterval>().0;
+    game.frame(step * 5);
+    assert_eq!(game.game_over().unwrap().tick, 2);
+    assert_eq!(game.app.world().resource::<GameTick>().0, 2);
+    assert_eq!(game.head(), at(3, 4));
+}
+
+#[test]
+fn new_game_clears_the_cause() {
+    let mut game = TestApp::playing();
+    game.app.world_mut().spawn((Obstacle, at(3, 4)));
+    game.tick();
+    game.update();
+    assert!(game.game_over().is_some());
+    game.set_state(GameState::Playing);
+    assert_eq!(game.game_over(), None);
+}
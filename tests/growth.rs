@@ -0,0 +1,97 @@
+mod common;
+
+use common::{at, TestApp};
+use snake::actions::control::GameControl;
+use snake::food::FoodKind;
+use snake::gameover::GameOverCause;
+use snake::save::{PendingRestore, SaveGame};
+use snake::snake::{GrowthEvent, GrowthSource, PendingGrowth, SnakeBlocks};
+use snake::GameState;
+
+fn grow(game: &mut TestApp, amount: i32) {
+    let world = game.app.world_mut();
+    let snake = world.resource::<SnakeBlocks>()[0];
+    world.send_event(GrowthEvent {
+        snake,
+        amount,
+        source: GrowthSource::Food(FoodKind::Normal),
+    });
+}
+
+fn pending(game: &TestApp) -> u32 {
+    game.app.world().resource::<PendingGrowth>().0
+}
+
+#[test]
+fn eating_grows_on_the_same_tick() {
+    let mut game = TestApp::playing();
+    game.spawn_food(at(3, 4));
+    game.tick();
+    assert_eq!(game.segments(), [at(3, 4), at(3, 3), at(3, 2)]);
+    assert_eq!(pending(&game), 0);
+}
+
+#[test]
+fn larger_growth_is_queued_one_block_per_tick() {
+    let mut game = TestApp::playing();
+    grow(&mut game, 3);
+    game.tick();
+    assert_eq!(game.segments(), [at(3, 4), at(3, 3), at(3, 2)]);
+    assert_eq!(pending(&game), 2);
+    game.advance_ticks(2);
+    assert_eq!(
+        game.segments(),
+        [at(3, 6), at(3, 5), at(3, 4), at(3, 3), at(3, 2)],
+        "尾巴停在原地"
+    );
+    assert_eq!(pending(&game), 0);
+    game.tick();
+    assert_eq!(game.segments().len(), 5);
+}
+
+#[test]
+fn shrinking_cancels_queued_growth_first() {
+    let mut game = TestApp::playing();
+    grow(&mut game, 3);
+    game.tick();
+    grow(&mut game, -1);
+    game.tick();
+    assert_eq!(game.segments().len(), 4, "抵消一节，还长一节");
+    assert_eq!(pending(&game), 0);
+
+    grow(&mut game, 3);
+    game.tick();
+    grow(&mut game, -4);
+    game.tick();
+    assert_eq!(pending(&game), 0);
+    assert_eq!(game.segments().len(), 3, "抵消两节，再去掉两节");
+}
+
+#[test]
+fn queued_growth_holds_the_tail() {
+    let mut game = TestApp::playing();
+    game.replace_snake(&[at(5, 5), at(5, 6), at(4, 6), at(4, 5)], GameControl::Left);
+    game.app.world_mut().insert_resource(PendingGrowth(1));
+    game.tick();
+    let event = game.game_over().unwrap();
+    assert_eq!(event.cause, GameOverCause::Body);
+}
+
+#[test]
+fn queued_growth_is_saved() {
+    let mut original = TestApp::seeded(3);
+    original.set_state(GameState::Playing);
+    grow(&mut original, 4);
+    original.tick();
+    let save = SaveGame::capture(original.app.world_mut()).unwrap();
+
+    let mut restored = TestApp::seeded(3);
+    restored.app.insert_resource(PendingRestore(save));
+    restored.set_state(GameState::Playing);
+    assert_eq!(pending(&restored), 3);
+    for game in [&mut original, &mut restored] {
+        game.advance_ticks(3);
+    }
+    assert_eq!(restored.segments(), original.segments());
+    assert_eq!(restored.segments().len(), 6);
+}
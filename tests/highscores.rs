@@ -0,0 +1,108 @@
+mod common;
+
+use common::TestApp;
+use snake::actions::Position;
+use snake::highscore::{HighScore, HighScores, BROKEN_HIGH_SCORE_FILE, MAX_HIGH_SCORES};
+use snake::mode::GameMode;
+use snake::GameState;
+
+fn entry(score: u32) -> HighScore {
+    HighScore {
+        score,
+        best_streak: 0,
+    }
+}
+
+#[test]
+fn keeps_the_best_scores_in_order() {
+    let mut scores = HighScores::default();
+    assert_eq!(scores.insert(GameMode::Classic, entry(5)), Some(0));
+    assert_eq!(scores.insert(GameMode::Classic, entry(9)), Some(0));
+    assert_eq!(scores.insert(GameMode::Classic, entry(7)), Some(1));
+    // 同分排在先达到的后面
+    assert_eq!(scores.insert(GameMode::Classic, entry(7)), Some(2));
+    assert_eq!(scores.insert(GameMode::Classic, entry(0)), None);
+
+    let values: Vec<u32> = scores
+        .entries(GameMode::Classic)
+        .iter()
+        .map(|e| e.score)
+        .collect();
+    assert_eq!(values, vec![9, 7, 7, 5]);
+}
+
+#[test]
+fn drops_scores_below_the_table() {
+    let mut scores = HighScores::default();
+    for score in 1..=MAX_HIGH_SCORES as u32 {
+        scores.insert(GameMode::Classic, entry(score * 10));
+    }
+    assert_eq!(scores.insert(GameMode::Classic, entry(5)), None);
+    assert_eq!(
+        scores.insert(GameMode::Classic, entry(15)),
+        Some(MAX_HIGH_SCORES - 1)
+    );
+    assert_eq!(scores.entries(GameMode::Classic).len(), MAX_HIGH_SCORES);
+    assert_eq!(scores.entries(GameMode::Classic).last().unwrap().score, 15);
+}
+
+#[test]
+fn saves_and_loads_from_a_file() {
+    let path = std::env::temp_dir()
+        .join(format!("snake-highscores-{}", std::process::id()))
+        .join("highscores.ron");
+    assert_eq!(HighScores::load(&path), HighScores::default());
+
+    let mut scores = HighScores::default();
+    scores.insert(GameMode::Classic, entry(12));
+    scores.save(&path).unwrap();
+    assert_eq!(HighScores::load(&path), scores);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn unreadable_files_are_set_aside() {
+    let dir = std::env::temp_dir().join(format!("snake-highscores-broken-{}", std::process::id()));
+    let path = dir.join("highscores.ron");
+    let garbage = b"{ not ron \xff\xfe".to_vec();
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, &garbage).unwrap();
+
+    assert_eq!(HighScores::load(&path), HighScores::default());
+    let broken = dir.join(BROKEN_HIGH_SCORE_FILE);
+    assert_eq!(std::fs::read(&broken).unwrap(), garbage);
+
+    // 下一局结束时写入新的排行榜，原来的文件不受影响
+    let mut scores = HighScores::default();
+    scores.insert(GameMode::Classic, entry(3));
+    scores.save(&path).unwrap();
+    assert_eq!(std::fs::read(&broken).unwrap(), garbage);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn game_over_records_the_score() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.tick();
+    game.update();
+
+    game.set_state(GameState::GameOver);
+    let scores = game.app.world().resource::<HighScores>();
+    assert_eq!(
+        scores.entries(GameMode::Classic),
+        &[HighScore {
+            score: 1,
+            best_streak: 1
+        }]
+    );
+}
+
+#[test]
+fn each_mode_has_its_own_table() {
+    let mut scores = HighScores::default();
+    scores.insert(GameMode::Classic, entry(3));
+    assert_eq!(scores.insert(GameMode::Survival, entry(1)), Some(0));
+    assert_eq!(scores.entries(GameMode::Survival), &[entry(1)]);
+    assert!(scores.entries(GameMode::TimeAttack(60)).is_empty());
+}
@@ -0,0 +1,77 @@
+use fluent_bundle::FluentValue;
+use snake::food::FoodKind;
+use snake::gameover::GameOverCause;
+use snake::i18n::{Language, Localizer};
+use snake::powerup::PowerUpRegistry;
+use snake::score::ScoreSource;
+
+#[test]
+fn catalogs_define_the_same_keys() {
+    let mut english = Language::English.keys();
+    let mut chinese = Language::Chinese.keys();
+    english.sort();
+    chinese.sort();
+    assert_eq!(english, chinese);
+}
+
+#[test]
+fn every_food_and_power_up_has_a_name() {
+    let registry = PowerUpRegistry::from_ron(include_str!("../assets/powerups.ron")).unwrap();
+    for language in Language::ALL {
+        let localizer = Localizer::new(language);
+        for kind in FoodKind::ALL {
+            assert!(
+                localizer.has(&format!("food-{kind:?}")),
+                "{language:?} {kind:?}"
+            );
+        }
+        for source in ScoreSource::all() {
+            assert!(localizer.has(&source.key()), "{language:?} {source:?}");
+        }
+        for def in &registry.0 {
+            assert!(
+                localizer.has(&format!("powerup-{}", def.name)),
+                "{language:?} {}",
+                def.name
+            );
+        }
+    }
+}
+
+#[test]
+fn every_game_over_cause_has_a_message() {
+    for language in Language::ALL {
+        let localizer = Localizer::new(language);
+        for cause in GameOverCause::ALL {
+            assert!(localizer.has(&cause.key()), "{language:?} {cause:?}");
+        }
+    }
+}
+
+#[test]
+fn switching_language_changes_the_text() {
+    let mut localizer = Localizer::default();
+    assert_eq!(localizer.get("game-over"), "Game Over");
+    localizer.set_language(Language::Chinese);
+    assert_eq!(localizer.get("game-over"), "游戏结束");
+    assert_eq!(localizer.language().next(), Language::English);
+}
+
+#[test]
+fn arguments_are_filled_in_without_isolation_marks() {
+    let localizer = Localizer::new(Language::Chinese);
+    assert_eq!(
+        localizer.get_with("best-streak", [("streak", FluentValue::from(7))]),
+        "最长连击：7"
+    );
+    assert_eq!(localizer.get("streak-label"), "  连击：");
+    let english = Localizer::new(Language::English);
+    assert_eq!(english.get("score-label"), "Score: ");
+}
+
+#[test]
+fn unknown_keys_fall_back_to_the_key() {
+    let localizer = Localizer::new(Language::Chinese);
+    assert_eq!(localizer.get("no-such-key"), "no-such-key");
+    assert_eq!(localizer.try_get("no-such-key"), None);
+}
@@ -0,0 +1,135 @@
+//! 用随机的按键序列和随机数种子跑游戏，每个节拍检查蛇和食物的不变量
+
+mod common;
+
+use bevy::prelude::*;
+use common::TestApp;
+use proptest::prelude::*;
+use snake::actions::Position;
+use snake::powerup::PowerUpRegistry;
+use snake::score::AddScoreEvent;
+use snake::snake::GrowthEvent;
+use snake::{GameState, ARENA_HEIGHT, ARENA_WIDTH};
+
+const KEYS: [Option<KeyCode>; 9] = [
+    None,
+    Some(KeyCode::ArrowUp),
+    Some(KeyCode::ArrowDown),
+    Some(KeyCode::ArrowLeft),
+    Some(KeyCode::ArrowRight),
+    Some(KeyCode::KeyW),
+    Some(KeyCode::KeyA),
+    Some(KeyCode::KeyS),
+    Some(KeyCode::KeyD),
+];
+
+/// 按到目前为止发出的计分和生长事件推算出的分数和长度
+#[derive(Resource)]
+struct Expected {
+    score: u32,
+    length: usize,
+}
+
+impl Default for Expected {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            length: 2,
+        }
+    }
+}
+
+fn track_events(
+    mut expected: ResMut<Expected>,
+    mut scores: EventReader<AddScoreEvent>,
+    mut growths: EventReader<GrowthEvent>,
+) {
+    for event in scores.read() {
+        expected.score = expected.score.saturating_add_signed(event.total());
+    }
+    for event in growths.read() {
+        expected.length = expected
+            .length
+            .saturating_add_signed(event.amount as isize)
+            .max(2);
+    }
+}
+
+fn in_bounds(pos: &Position) -> bool {
+    (0..ARENA_WIDTH as i32).contains(&pos.x) && (0..ARENA_HEIGHT as i32).contains(&pos.y)
+}
+
+fn check_invariants(game: &mut TestApp) -> Result<(), TestCaseError> {
+    let segments = game.segments();
+    let expected = game.app.world().resource::<Expected>();
+    let (score, length) = (expected.score, expected.length);
+
+    for pair in segments.windows(2) {
+        let distance = (pair[0].x - pair[1].x).abs() + (pair[0].y - pair[1].y).abs();
+        prop_assert_eq!(distance, 1, "segments not contiguous: {:?}", segments);
+    }
+    for (i, pos) in segments.iter().enumerate() {
+        prop_assert!(in_bounds(pos), "segment out of bounds: {:?}", segments);
+        prop_assert!(
+            !segments[i + 1..].contains(pos),
+            "segments overlap: {:?}",
+            segments
+        );
+    }
+    prop_assert_eq!(segments.len(), length);
+    prop_assert_eq!(game.score(), score);
+    for food in game.food() {
+        prop_assert!(in_bounds(&food), "food out of bounds: {:?}", food);
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn snake_invariants_hold_every_tick(
+        seed in any::<u64>(),
+        inputs in prop::collection::vec(prop::sample::select(KEYS.to_vec()), 1..300),
+    ) {
+        let mut game = TestApp::seeded(seed);
+        // 道具会改变长度和得分规则，这里只检查基础规则
+        game.app
+            .insert_resource(PowerUpRegistry::default())
+            .init_resource::<Expected>()
+            .add_systems(Last, track_events);
+        game.set_state(GameState::Playing);
+        check_invariants(&mut game)?;
+
+        for key in inputs {
+            if let Some(key) = key {
+                game.press(key);
+            }
+            game.tick();
+            // 撞墙或撞到自己的这一拍不检查
+            if game.pending_state().is_some() {
+                break;
+            }
+            check_invariants(&mut game)?;
+        }
+    }
+
+    #[test]
+    fn same_seed_and_inputs_replay_identically(
+        seed in any::<u64>(),
+        inputs in prop::collection::vec(prop::sample::select(KEYS.to_vec()), 1..100),
+    ) {
+        let run = || {
+            let mut game = TestApp::seeded(seed);
+            game.set_state(GameState::Playing);
+            for key in &inputs {
+                if let Some(key) = key {
+                    game.press(*key);
+                }
+                game.tick();
+            }
+            (game.segments(), game.food(), game.score(), game.state())
+        };
+        prop_assert_eq!(run(), run());
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::math::Vec2;
+use snake::actions::Position;
+use snake::layout::{Layout, HUD_HEIGHT};
+use snake::Arena;
+
+const ARENA: Arena = Arena {
+    width: 25,
+    height: 25,
+};
+
+// 竞技场完整地落在窗口内且不压住顶部记分牌
+fn assert_fits(layout: &Layout, window: Vec2, arena: &Arena) {
+    let eps = 1e-3;
+    let min = layout.center - layout.size / 2.0;
+    let max = layout.center + layout.size / 2.0;
+    assert!(min.x >= -window.x / 2.0 - eps, "{layout:?} in {window}");
+    assert!(min.y >= -window.y / 2.0 - eps, "{layout:?} in {window}");
+    assert!(max.x <= window.x / 2.0 + eps, "{layout:?} in {window}");
+    assert!(
+        max.y <= window.y / 2.0 - HUD_HEIGHT + eps,
+        "{layout:?} in {window}"
+    );
+    let corner = layout.position_to_world(Position {
+        x: arena.width as i32 - 1,
+        y: arena.height as i32 - 1,
+    });
+    assert!((corner - (max - layout.cell / 2.0)).length() < eps);
+}
+
+#[test]
+fn cells_stay_square_for_any_window() {
+    for (w, h) in [
+        (500.0, 500.0),
+        (800.0, 600.0),
+        (600.0, 800.0),
+        (1920.0, 1080.0),
+        (300.0, 1000.0),
+        (1000.0, 100.0),
+    ] {
+        let window = Vec2::new(w, h);
+        let layout = Layout::new(window, &ARENA, HUD_HEIGHT);
+        assert_eq!(layout.size.x / 25.0, layout.cell);
+        assert_eq!(layout.size.y / 25.0, layout.cell);
+        assert_fits(&layout, window, &ARENA);
+        // 至少有一个方向被撑满
+        let filled_x = (layout.size.x - w).abs() < 1e-3;
+        let filled_y = (layout.size.y - (h - HUD_HEIGHT)).abs() < 1e-3;
+        assert!(filled_x || filled_y, "{layout:?} in {window}");
+    }
+}
+
+#[test]
+fn wide_window_letterboxes_the_sides() {
+    let layout = Layout::new(Vec2::new(1040.0, 540.0), &ARENA, HUD_HEIGHT);
+    assert_eq!(layout.cell, 20.0);
+    assert_eq!(layout.center, Vec2::new(0.0, -20.0));
+    assert_eq!(
+        layout.position_to_world(Position { x: 0, y: 0 }),
+        Vec2::new(-240.0, -260.0)
+    );
+}
+
+#[test]
+fn tall_window_letterboxes_top_and_bottom() {
+    let layout = Layout::new(Vec2::new(500.0, 1040.0), &ARENA, HUD_HEIGHT);
+    assert_eq!(layout.cell, 20.0);
+    assert_eq!(layout.size, Vec2::new(500.0, 500.0));
+    assert_eq!(layout.center, Vec2::new(0.0, -20.0));
+}
+
+#[test]
+fn non_square_arena() {
+    let arena = Arena {
+        width: 40,
+        height: 20,
+    };
+    for (w, h) in [(800.0, 440.0), (500.0, 500.0), (1280.0, 720.0)] {
+        let window = Vec2::new(w, h);
+        let layout = Layout::new(window, &arena, HUD_HEIGHT);
+        assert_eq!(layout.size, Vec2::new(40.0, 20.0) * layout.cell);
+        assert_fits(&layout, window, &arena);
+    }
+    let layout = Layout::new(Vec2::new(800.0, 440.0), &arena, HUD_HEIGHT);
+    assert_eq!(layout.cell, 20.0);
+}
+
+#[test]
+fn tiny_window_does_not_go_negative() {
+    let layout = Layout::new(Vec2::new(10.0, 20.0), &ARENA, HUD_HEIGHT);
+    assert_eq!(layout.cell, 0.0);
+    assert_eq!(layout.size, Vec2::ZERO);
+}
+
+#[test]
+fn interpolated_cells_sit_between_grid_positions() {
+    let layout = Layout::new(Vec2::new(500.0, 540.0), &ARENA, HUD_HEIGHT);
+    let a = layout.position_to_world(Position { x: 3, y: 3 });
+    let b = layout.position_to_world(Position { x: 4, y: 3 });
+    assert_eq!(layout.cell_to_world(Vec2::new(3.5, 3.0)), (a + b) / 2.0);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::Position;
+use snake::mode::{GameMode, SURVIVAL_OBSTACLE_SECS, SURVIVAL_POINT_SECS};
+use snake::obstacle::{spawn_obstacle_at, Obstacle, OBSTACLE_CLEARANCE};
+use snake::score::{ScoreLog, ScoreSource};
+use snake::GameState;
+
+fn obstacles(game: &mut TestApp) -> Vec<Position> {
+    game.app
+        .world_mut()
+        .query_filtered::<&Position, With<Obstacle>>()
+        .iter(game.app.world())
+        .copied()
+        .collect()
+}
+
+#[test]
+fn time_attack_ends_when_time_runs_out() {
+    let mut game = TestApp::playing_mode(GameMode::TimeAttack(60));
+    game.fast_forward(59.0);
+    assert_eq!(game.state(), GameState::Playing);
+    game.fast_forward(1.0);
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn classic_has_no_time_limit() {
+    let mut game = TestApp::playing_mode(GameMode::Classic);
+    game.fast_forward(600.0);
+    assert_eq!(game.state(), GameState::Playing);
+    assert!(obstacles(&mut game).is_empty());
+}
+
+#[test]
+fn survival_adds_obstacles_away_from_head_and_scores_time() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    game.fast_forward(SURVIVAL_POINT_SECS);
+    assert_eq!(game.score(), 1);
+    game.fast_forward(SURVIVAL_OBSTACLE_SECS - SURVIVAL_POINT_SECS);
+    assert_eq!(game.state(), GameState::Playing);
+    let head = game.head();
+    let obstacles = obstacles(&mut game);
+    assert_eq!(obstacles.len(), 1);
+    for pos in obstacles {
+        assert!((pos.x - head.x).abs() + (pos.y - head.y).abs() > OBSTACLE_CLEARANCE);
+    }
+    // 每存活 5 秒加一分，和吃食物一样记入得分明细
+    assert_eq!(game.score(), 2);
+    let log = game.app.world().resource::<ScoreLog>();
+    assert_eq!(log.by_source(), vec![(ScoreSource::Survival, 2, 2)]);
+}
+
+#[test]
+fn hitting_an_obstacle_ends_the_game() {
+    let mut game = TestApp::playing_mode(GameMode::Survival);
+    let head = game.head();
+    let world = game.app.world_mut();
+    spawn_obstacle_at(
+        &mut world.commands(),
+        Position {
+            x: head.x,
+            y: head.y + 1,
+        },
+    );
+    world.flush();
+    game.tick();
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::TestApp;
+use snake::actions::Position;
+use snake::portal::{Portal, PortalConfig, RANDOM_PORTAL_PAIRS};
+use snake::save::{PendingRestore, SaveGame};
+use snake::GameState;
+
+const ENTRY: Position = Position { x: 3, y: 5 };
+const EXIT: Position = Position { x: 10, y: 10 };
+
+fn playing_with(config: PortalConfig, seed: u64) -> TestApp {
+    let mut game = TestApp::seeded(seed);
+    game.app.insert_resource(config);
+    game.set_state(GameState::Playing);
+    game
+}
+
+fn portals(game: &mut TestApp) -> Vec<(Position, Position)> {
+    let mut portals = game
+        .app
+        .world_mut()
+        .query::<(&Position, &Portal)>()
+        .iter(game.app.world())
+        .map(|(&pos, portal)| (pos, portal.partner))
+        .collect::<Vec<_>>();
+    portals.sort_by_key(|(pos, _)| (pos.x, pos.y));
+    portals
+}
+
+#[test]
+fn head_comes_out_of_partner_and_body_follows() {
+    let mut game = playing_with(
+        PortalConfig {
+            pairs: vec![(ENTRY, EXIT)],
+            random_pairs: 0,
+        },
+        0,
+    );
+    // 蛇头在 (3, 3) 向上走
+    game.advance_ticks(2);
+    assert_eq!(game.segments(), [EXIT, Position { x: 3, y: 4 }]);
+    game.tick();
+    assert_eq!(
+        game.segments(),
+        [Position { x: 10, y: 11 }, EXIT],
+        "方向不变，蛇身跟着穿过"
+    );
+    assert_eq!(game.state(), GameState::Playing);
+}
+
+#[test]
+fn random_portals_follow_the_seed() {
+    let config = PortalConfig {
+        pairs: Vec::new(),
+        random_pairs: 2,
+    };
+    let mut a = playing_with(config.clone(), 5);
+    let mut b = playing_with(config, 5);
+    let placed = portals(&mut a);
+    assert_eq!(placed.len(), 4);
+    assert_eq!(placed, portals(&mut b));
+    for &(pos, partner) in &placed {
+        assert!(placed.contains(&(partner, pos)));
+    }
+}
+
+#[test]
+fn portals_survive_save_and_restore() {
+    let mut original = playing_with(
+        PortalConfig {
+            pairs: vec![(ENTRY, EXIT)],
+            random_pairs: 1,
+        },
+        3,
+    );
+    let save = SaveGame::capture(original.app.world_mut()).unwrap();
+    let mut restored = TestApp::seeded(4);
+    restored.app.insert_resource(PendingRestore(save));
+    restored.set_state(GameState::Playing);
+    assert_eq!(portals(&mut restored), portals(&mut original));
+}
+
+#[test]
+fn settings_turn_random_portals_on() {
+    let mut game = TestApp::new();
+    game.configure(|settings| settings.portals = true);
+    game.set_state(GameState::Playing);
+    assert_eq!(portals(&mut game).len(), 2 * RANDOM_PORTAL_PAIRS as usize);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::KeyCode;
+use common::TestApp;
+use snake::actions::Position;
+use snake::obstacle::Obstacle;
+use snake::powerup::{ActiveEffects, Effect, PowerUpDef, PowerUpError, PowerUpRegistry, Stacking};
+use snake::GameState;
+
+/// 沿 y 轴向上吃三个食物，蛇长到五节，蛇头在 (3, 6)
+fn grown_snake() -> TestApp {
+    let mut game = TestApp::playing();
+    for y in 4..7 {
+        game.spawn_food(Position { x: 3, y });
+    }
+    game.advance_ticks(3);
+    assert_eq!(game.segments().len(), 5);
+    game
+}
+
+/// 从 (3, 6) 绕回去撞到 (3, 5) 的身体
+fn turn_back_into_body(game: &mut TestApp) {
+    game.press(KeyCode::ArrowRight);
+    game.tick();
+    game.press(KeyCode::ArrowDown);
+    game.tick();
+    game.press(KeyCode::ArrowLeft);
+    game.tick();
+}
+
+#[test]
+fn running_into_own_body_is_game_over() {
+    let mut game = grown_snake();
+    turn_back_into_body(&mut game);
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn ghost_passes_through_own_body() {
+    let mut game = grown_snake();
+    game.spawn_power_up("Ghost", Position { x: 4, y: 6 });
+    turn_back_into_body(&mut game);
+    game.update();
+    assert_eq!(game.state(), GameState::Playing);
+    assert_eq!(game.head(), Position { x: 3, y: 5 });
+}
+
+#[test]
+fn shrink_drops_tail_segments() {
+    let mut game = grown_snake();
+    game.spawn_power_up("Shrink", Position { x: 3, y: 7 });
+    game.tick();
+    game.update();
+    assert_eq!(
+        game.segments(),
+        vec![Position { x: 3, y: 7 }, Position { x: 3, y: 6 }]
+    );
+}
+
+#[test]
+fn double_score_stacks_up_to_its_limit() {
+    let mut game = TestApp::playing();
+    for y in 4..7 {
+        game.spawn_power_up("Double", Position { x: 3, y });
+    }
+    game.spawn_food(Position { x: 3, y: 7 });
+    game.advance_ticks(4);
+    // 最多叠加两层，得分乘以 4
+    assert_eq!(game.score(), 4);
+}
+
+#[test]
+fn slow_motion_halves_the_speed() {
+    let mut game = TestApp::playing();
+    game.spawn_power_up("Slow", Position { x: 3, y: 4 });
+    game.tick();
+    game.advance_ticks(4);
+    assert_eq!(game.head(), Position { x: 3, y: 6 });
+}
+
+#[test]
+fn magnet_pulls_nearby_food_towards_the_head() {
+    let mut game = TestApp::playing();
+    game.spawn_power_up("Magnet", Position { x: 3, y: 4 });
+    game.spawn_food(Position { x: 6, y: 5 });
+    game.tick();
+    game.tick();
+    assert_eq!(game.food(), vec![Position { x: 5, y: 5 }]);
+}
+
+#[test]
+fn magnet_leaves_food_that_would_land_on_something() {
+    let mut game = TestApp::playing();
+    game.spawn_power_up("Magnet", Position { x: 3, y: 4 });
+    game.app
+        .world_mut()
+        .spawn((Obstacle, Position { x: 5, y: 5 }));
+    game.spawn_food(Position { x: 6, y: 5 });
+    // 第二拍蛇身在 (3, 4)
+    game.spawn_food(Position { x: 4, y: 4 });
+    game.tick();
+    game.tick();
+    let mut food = game.food();
+    food.sort_by_key(|pos| (pos.x, pos.y));
+    assert_eq!(food, [Position { x: 4, y: 4 }, Position { x: 6, y: 5 }]);
+}
+
+#[test]
+fn slow_motion_factor_must_be_positive() {
+    let def = |factor: &str| {
+        format!(
+            "[(name: \"Slow\", effect: SlowMotion({factor}), duration: 1.0, stacking: Refresh, weight: 1, color: (0.0, 0.0, 0.0))]"
+        )
+    };
+    assert!(PowerUpRegistry::from_ron(&def("0.5")).is_ok());
+    for factor in ["0.0", "-1.0", "NaN"] {
+        assert!(
+            matches!(
+                PowerUpRegistry::from_ron(&def(factor)),
+                Err(PowerUpError::InvalidSlowMotion { .. })
+            ),
+            "{factor}"
+        );
+    }
+}
+
+#[test]
+fn stacking_rules_refresh_or_extend_the_timer() {
+    let def = |stacking| PowerUpDef {
+        name: String::new(),
+        effect: Effect::Ghost,
+        duration: 4.0,
+        stacking,
+        weight: 1,
+        color: (1.0, 1.0, 1.0),
+    };
+    for (stacking, remaining) in [(Stacking::Refresh, 4.0), (Stacking::Extend, 7.0)] {
+        let mut effects = ActiveEffects::default();
+        effects.activate(0, &def(stacking));
+        effects.0[0].timer.tick(Duration::from_secs(1));
+        effects.activate(0, &def(stacking));
+        assert_eq!(effects.0.len(), 1);
+        assert_eq!(effects.0[0].timer.remaining_secs(), remaining);
+    }
+}
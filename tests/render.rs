@@ -0,0 +1,58 @@
+#![cfg(feature = "render")]
+
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::Position;
+use snake::enemy::{Enemy, EnemyKind};
+use snake::layout::Layout;
+use snake::render::{position_translation, track_moves, MovementStyle, Tween};
+use snake::tick::TickInterval;
+use snake::Arena;
+
+fn smooth_game() -> TestApp {
+    let mut game = TestApp::playing();
+    game.app
+        .insert_resource(Layout::new(Vec2::splat(500.0), &Arena::default(), 0.0))
+        .insert_resource(MovementStyle::Smooth)
+        .add_systems(FixedPostUpdate, track_moves)
+        .add_systems(Update, position_translation);
+    game
+}
+
+fn rendered_at_cell(game: &TestApp, entity: Entity) -> bool {
+    let world = game.app.world();
+    let pos = *world.get::<Position>(entity).unwrap();
+    let translation = world.get::<Transform>(entity).unwrap().translation;
+    translation.truncate() == world.resource::<Layout>().position_to_world(pos)
+}
+
+#[test]
+fn idle_enemies_stay_on_their_cell() {
+    let mut game = smooth_game();
+    let start = Position { x: 10, y: 10 };
+    let wanderer = game
+        .app
+        .world_mut()
+        .spawn((
+            Enemy::new(EnemyKind::Wanderer),
+            start,
+            Tween::at(start),
+            Transform::default(),
+        ))
+        .id();
+    // 游荡者隔拍才走：第三拍走一格，第四拍停着
+    game.advance_ticks(3);
+    assert_ne!(*game.app.world().get::<Position>(wanderer).unwrap(), start);
+    game.tick();
+    assert!(rendered_at_cell(&game, wanderer));
+
+    let half = game.app.world().resource::<TickInterval>().0 / 2;
+    game.frame(half);
+    assert!(rendered_at_cell(&game, wanderer), "两拍之间也不回退");
+    game.frame(Duration::ZERO);
+    assert!(rendered_at_cell(&game, wanderer));
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::Position;
+use snake::save::{PendingRestore, SaveError, SaveGame, BROKEN_SAVE_FILE, SAVE_FILE, SAVE_VERSION};
+use snake::settings::DataDir;
+use snake::{GameState, PauseState};
+
+fn capture(game: &mut TestApp) -> SaveGame {
+    SaveGame::capture(game.app.world_mut()).unwrap()
+}
+
+fn set_pause(game: &mut TestApp, state: PauseState) {
+    game.app
+        .world_mut()
+        .resource_mut::<NextState<PauseState>>()
+        .set(state);
+    game.update();
+}
+
+fn temp_data_dir(name: &str) -> DataDir {
+    DataDir(Some(std::env::temp_dir().join(format!(
+        "snake-{}-{}",
+        name,
+        std::process::id()
+    ))))
+}
+
+#[test]
+fn restored_game_continues_exactly() {
+    let mut original = TestApp::seeded(7);
+    original.set_state(GameState::Playing);
+    original.spawn_food(Position { x: 3, y: 5 });
+    original.advance_ticks(15);
+    let save = SaveGame::from_ron(&capture(&mut original).to_ron().unwrap()).unwrap();
+
+    let mut restored = TestApp::seeded(99);
+    restored.app.insert_resource(PendingRestore(save));
+    restored.set_state(GameState::Playing);
+    assert_eq!(restored.segments(), original.segments());
+    assert_eq!(restored.score(), original.score());
+
+    for game in [&mut original, &mut restored] {
+        game.press(KeyCode::ArrowRight);
+        game.advance_ticks(15);
+    }
+    assert_eq!(restored.segments(), original.segments());
+    assert_eq!(restored.score(), original.score());
+    let (mut a, mut b) = (original.food(), restored.food());
+    a.sort_by_key(|pos| (pos.x, pos.y));
+    b.sort_by_key(|pos| (pos.x, pos.y));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn rejects_saves_from_other_versions() {
+    let mut game = TestApp::playing();
+    let source = capture(&mut game).to_ron().unwrap().replace(
+        &format!("version: {}", SAVE_VERSION),
+        &format!("version: {}", SAVE_VERSION + 1),
+    );
+    let err = SaveGame::from_ron(&source).err().unwrap();
+    assert!(matches!(
+        err,
+        SaveError::Version { found, expected } if found == SAVE_VERSION + 1 && expected == SAVE_VERSION
+    ));
+    assert!(err.to_string().contains(&(SAVE_VERSION + 1).to_string()));
+}
+
+#[test]
+fn pausing_saves_and_dying_deletes_the_save() {
+    let dir = temp_data_dir("autosave");
+    let path = dir.file(SAVE_FILE).unwrap();
+    let mut game = TestApp::playing();
+    game.app.insert_resource(dir.clone());
+    game.advance_ticks(2);
+
+    set_pause(&mut game, PauseState::Paused);
+    let save = SaveGame::load(&path).unwrap();
+    assert_eq!(save.snake, game.segments());
+
+    set_pause(&mut game, PauseState::Running);
+    game.set_state(GameState::GameOver);
+    assert!(!path.exists());
+    std::fs::remove_dir_all(dir.0.unwrap()).unwrap();
+}
+
+#[test]
+fn saves_that_cannot_be_restored_are_set_aside() {
+    let dir = temp_data_dir("broken");
+    let path = dir.file(SAVE_FILE).unwrap();
+    let mut original = TestApp::playing();
+    original.spawn_power_up("Ghost", Position { x: 3, y: 4 });
+    original.tick();
+    let mut save = capture(&mut original);
+    save.effects[0].name = "Missing".to_string();
+    save.save(&path).unwrap();
+
+    let mut game = TestApp::new();
+    game.app.insert_resource(dir.clone());
+    game.app.insert_resource(PendingRestore(save));
+    game.set_state(GameState::Playing);
+    assert_eq!(game.head(), Position { x: 3, y: 3 }, "开始新的一局");
+    assert!(!path.exists(), "不再提供继续");
+    assert!(dir.file(BROKEN_SAVE_FILE).unwrap().exists());
+    std::fs::remove_dir_all(dir.0.unwrap()).unwrap();
+}
+
+#[test]
+fn leaving_for_the_menu_clears_the_board() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.tick();
+    game.update();
+    set_pause(&mut game, PauseState::Paused);
+
+    game.set_state(GameState::Menu);
+    assert!(game.segments().is_empty());
+    assert!(game.food().is_empty());
+    assert_eq!(game.score(), 0);
+
+    // 新的一局不再处于暂停状态
+    game.set_state(GameState::Playing);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+}
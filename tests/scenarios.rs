@@ -0,0 +1,177 @@
+mod common;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::Position;
+use snake::food::FoodKind;
+use snake::score::{AddScoreEvent, ComboRules, ScoreSource};
+use snake::snake::{GrowthEvent, GrowthSource, SnakeHead};
+use snake::{Arena, GameState};
+
+#[test]
+fn starts_in_menu_and_spawns_snake_on_play() {
+    let mut game = TestApp::new();
+    assert_eq!(game.state(), GameState::Menu);
+
+    game.set_state(GameState::Playing);
+    assert_eq!(
+        game.segments(),
+        vec![Position { x: 3, y: 3 }, Position { x: 3, y: 2 }]
+    );
+}
+
+#[test]
+fn moves_one_cell_per_tick() {
+    let mut game = TestApp::playing();
+    game.advance_ticks(2);
+    assert_eq!(
+        game.segments(),
+        vec![Position { x: 3, y: 5 }, Position { x: 3, y: 4 }]
+    );
+}
+
+#[test]
+fn turns_with_arrow_keys_and_wasd() {
+    let mut game = TestApp::playing();
+    game.press(KeyCode::ArrowRight);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 4, y: 3 });
+
+    game.press(KeyCode::KeyS);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 4, y: 2 });
+}
+
+#[test]
+fn ignores_reversing_into_itself() {
+    let mut game = TestApp::playing();
+    game.press(KeyCode::ArrowDown);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+    assert_eq!(game.state(), GameState::Playing);
+}
+
+#[test]
+fn hitting_the_right_wall_is_game_over() {
+    let mut game = TestApp::playing();
+    game.press(KeyCode::ArrowRight);
+    // 从 x = 3 走到 x = 24 还在场内
+    game.advance_ticks(21);
+    assert_eq!(game.head().x, 24);
+    assert_eq!(game.state(), GameState::Playing);
+
+    game.tick();
+    game.update();
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn eating_food_increments_score_and_grows() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+
+    game.tick();
+    game.update();
+    assert_eq!(game.score(), 1);
+    assert!(game.food().is_empty());
+    assert_eq!(
+        game.segments(),
+        vec![
+            Position { x: 3, y: 4 },
+            Position { x: 3, y: 3 },
+            Position { x: 3, y: 2 },
+        ]
+    );
+}
+
+#[test]
+fn game_over_clears_the_board_and_score() {
+    let mut game = TestApp::playing();
+    game.spawn_food(Position { x: 3, y: 4 });
+    game.tick();
+    game.update();
+    assert_eq!(game.score(), 1);
+
+    game.set_state(GameState::GameOver);
+    assert_eq!(game.score(), 0);
+    assert!(game.segments().is_empty());
+    assert!(game.food().is_empty());
+}
+
+#[test]
+fn eating_two_foods_in_one_tick_counts_both() {
+    let mut game = TestApp::playing();
+    game.app.insert_resource(ComboRules::off());
+    game.spawn_food_of(FoodKind::Normal, Position { x: 3, y: 4 });
+    game.spawn_food_of(FoodKind::Golden, Position { x: 3, y: 4 });
+
+    game.tick();
+    assert_eq!(game.score(), 6);
+    // 一拍只长一节，多出来的下一拍再长
+    assert_eq!(game.segments().len(), 3);
+    game.tick();
+    assert_eq!(game.segments().len(), 4);
+}
+
+#[derive(Resource, Default)]
+struct Recorded(Vec<AddScoreEvent>, Vec<GrowthEvent>);
+
+fn record(
+    mut recorded: ResMut<Recorded>,
+    mut scores: EventReader<AddScoreEvent>,
+    mut growths: EventReader<GrowthEvent>,
+) {
+    recorded.0.extend(scores.read().copied());
+    recorded.1.extend(growths.read().copied());
+}
+
+#[test]
+fn events_carry_the_eating_snake_and_food() {
+    let mut game = TestApp::playing();
+    game.app
+        .init_resource::<Recorded>()
+        .add_systems(Last, record);
+    game.spawn_power_up("Double", Position { x: 3, y: 4 });
+    game.spawn_food_of(FoodKind::Golden, Position { x: 3, y: 5 });
+    game.advance_ticks(2);
+
+    let head = game
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<SnakeHead>>()
+        .single(game.app.world());
+    let recorded = game.app.world().resource::<Recorded>();
+    assert_eq!(
+        recorded.0,
+        vec![AddScoreEvent {
+            snake: head,
+            source: ScoreSource::Food(FoodKind::Golden),
+            position: Position { x: 3, y: 5 },
+            points: 5,
+            multiplier: 2,
+            streak: 1,
+        }]
+    );
+    assert_eq!(
+        recorded.1,
+        vec![GrowthEvent {
+            snake: head,
+            amount: 1,
+            source: GrowthSource::Food(FoodKind::Golden),
+        }]
+    );
+    assert_eq!(game.score(), 10);
+}
+
+#[test]
+fn walls_follow_the_configured_arena() {
+    let mut game = TestApp::playing();
+    game.app.insert_resource(Arena {
+        width: 40,
+        height: 5,
+    });
+    game.tick();
+    assert_eq!(game.pending_state(), None);
+    game.tick();
+    assert_eq!(game.pending_state(), Some(GameState::GameOver));
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use common::TestApp;
+use snake::actions::control::{GameControl, KeyBindings};
+use snake::actions::Position;
+use snake::i18n::Language;
+use snake::settings::{ArenaSize, Settings, Speed};
+use snake::snake::WallMode;
+use snake::tick::TickInterval;
+use snake::{Arena, GameState, PauseState};
+
+#[test]
+fn settings_round_trip_through_ron() {
+    let mut settings = Settings {
+        arena: ArenaSize::Wide,
+        speed: Speed::Fast,
+        wall_mode: WallMode::Wrap,
+        theme: "High Contrast".to_string(),
+        volume: 0.5,
+        language: Language::Chinese,
+        show_fps: true,
+        ..Settings::default()
+    };
+    settings.key_bindings.bind(GameControl::Up, KeyCode::KeyI);
+
+    let source = settings.to_ron().unwrap();
+    assert_eq!(Settings::from_ron(&source).unwrap(), settings);
+}
+
+#[test]
+fn missing_fields_fall_back_to_defaults() {
+    let settings = Settings::from_ron("(speed: Fast, show_fps: true)").unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            speed: Speed::Fast,
+            show_fps: true,
+            ..Settings::default()
+        }
+    );
+    assert!(Settings::from_ron("(speed: Warp)").is_err());
+}
+
+#[test]
+fn saves_and_loads_from_a_file() {
+    let path = std::env::temp_dir()
+        .join(format!("snake-settings-{}", std::process::id()))
+        .join("settings.ron");
+    assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+    let settings = Settings {
+        arena: ArenaSize::Small,
+        effects: false,
+        ..Settings::default()
+    };
+    settings.save(&path).unwrap();
+    assert_eq!(Settings::load(&path).unwrap(), settings);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn binding_a_key_takes_it_from_other_controls() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind(GameControl::Left, KeyCode::KeyD);
+    assert_eq!(bindings.keys(GameControl::Left), &[KeyCode::KeyD]);
+    assert_eq!(bindings.keys(GameControl::Right), &[KeyCode::ArrowRight]);
+}
+
+#[test]
+fn taking_the_only_key_of_a_control_swaps_keys() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind(GameControl::Up, KeyCode::KeyI);
+    bindings.bind(GameControl::Down, KeyCode::KeyK);
+    bindings.bind(GameControl::Down, KeyCode::KeyI);
+    assert_eq!(bindings.keys(GameControl::Down), &[KeyCode::KeyI]);
+    assert_eq!(bindings.keys(GameControl::Up), &[KeyCode::KeyK]);
+    for control in GameControl::ALL {
+        assert!(!bindings.keys(control).is_empty(), "{control:?}");
+    }
+}
+
+#[test]
+fn custom_key_bindings_steer_the_snake() {
+    let mut game = TestApp::playing();
+    game.configure(|settings| {
+        settings
+            .key_bindings
+            .bind(GameControl::Right, KeyCode::KeyL)
+    });
+
+    game.press(KeyCode::ArrowRight);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+
+    game.press(KeyCode::KeyL);
+    game.tick();
+    assert_eq!(game.head(), Position { x: 4, y: 4 });
+}
+
+#[test]
+fn wrapping_walls_come_out_the_other_side() {
+    let mut game = TestApp::playing();
+    game.configure(|settings| settings.wall_mode = WallMode::Wrap);
+
+    game.press(KeyCode::ArrowLeft);
+    game.advance_ticks(4);
+    assert_eq!(game.head(), Position { x: 24, y: 3 });
+    assert_eq!(game.pending_state(), None);
+}
+
+#[test]
+fn speed_sets_the_move_interval() {
+    let mut game = TestApp::playing();
+    game.configure(|settings| settings.speed = Speed::Fast);
+    let interval = Duration::from_secs_f32(Speed::Fast.move_interval());
+    assert_eq!(game.app.world().resource::<TickInterval>().0, interval);
+    assert_eq!(
+        game.app.world().resource::<Time<Fixed>>().timestep(),
+        interval
+    );
+
+    game.tick();
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+}
+
+#[test]
+fn arena_size_takes_effect_on_the_next_game() {
+    let mut game = TestApp::playing();
+    game.configure(|settings| settings.arena = ArenaSize::Small);
+    assert_eq!(*game.app.world().resource::<Arena>(), Arena::default());
+
+    game.set_state(GameState::GameOver);
+    game.set_state(GameState::Playing);
+    assert_eq!(
+        *game.app.world().resource::<Arena>(),
+        ArenaSize::Small.arena()
+    );
+}
+
+#[test]
+fn pausing_stops_the_snake() {
+    let mut game = TestApp::playing();
+    game.app
+        .world_mut()
+        .resource_mut::<NextState<PauseState>>()
+        .set(PauseState::Paused);
+    game.update();
+
+    game.press(KeyCode::ArrowRight);
+    game.advance_ticks(3);
+    assert_eq!(game.head(), Position { x: 3, y: 3 });
+
+    game.app
+        .world_mut()
+        .resource_mut::<NextState<PauseState>>()
+        .set(PauseState::Running);
+    game.update();
+    game.tick();
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+}
@@ -0,0 +1,134 @@
+use bevy::color::{Color, ColorToComponents, LinearRgba, Luminance, Srgba};
+use snake::food::FoodKind;
+use snake::theme::{Theme, ThemeRegistry};
+
+fn registry() -> ThemeRegistry {
+    ThemeRegistry::from_ron(include_str!("../assets/themes.ron")).unwrap()
+}
+
+// 地图上需要互相区分的颜色：竞技场、蛇头、蛇身、障碍物、传送门、敌人和每种食物
+fn board_colors(theme: &Theme) -> Vec<(String, Color)> {
+    let mut colors = vec![
+        ("arena".to_string(), theme.arena.0),
+        ("snake_head".to_string(), theme.snake_head.0),
+        ("snake_body".to_string(), theme.snake_body.0),
+        ("obstacle".to_string(), theme.obstacle.0),
+        ("portal".to_string(), theme.portal.0),
+        ("enemy".to_string(), theme.enemy.0),
+    ];
+    colors.extend(
+        FoodKind::ALL
+            .iter()
+            .map(|&kind| (format!("{kind:?}"), theme.food(kind))),
+    );
+    colors
+}
+
+fn contrast(a: Color, b: Color) -> f32 {
+    let (a, b) = (a.luminance() + 0.05, b.luminance() + 0.05);
+    a.max(b) / a.min(b)
+}
+
+// Machado 2009 的色觉模拟矩阵（严重程度 1.0，作用于线性 RGB）
+const PROTANOPIA: [[f32; 3]; 3] = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+const DEUTERANOPIA: [[f32; 3]; 3] = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+fn simulate(matrix: &[[f32; 3]; 3], color: Color) -> Srgba {
+    let rgb = LinearRgba::from(color).to_f32_array_no_alpha();
+    let [r, g, b] =
+        matrix.map(|row| (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, 1.0));
+    Srgba::from(LinearRgba::rgb(r, g, b))
+}
+
+fn distance(a: Srgba, b: Srgba) -> f32 {
+    let (a, b) = (a.to_f32_array_no_alpha(), b.to_f32_array_no_alpha());
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+fn min_distance(theme: &Theme, see: impl Fn(Color) -> Srgba) -> (f32, String, String) {
+    let colors = board_colors(theme);
+    let mut closest = (f32::MAX, String::new(), String::new());
+    for (i, (name_a, a)) in colors.iter().enumerate() {
+        for (name_b, b) in &colors[i + 1..] {
+            let d = distance(see(*a), see(*b));
+            if d < closest.0 {
+                closest = (d, name_a.clone(), name_b.clone());
+            }
+        }
+    }
+    closest
+}
+
+#[test]
+fn bundled_themes_parse() {
+    let registry = registry();
+    let names = registry
+        .0
+        .iter()
+        .map(|theme| theme.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["Classic", "High Contrast", "Deuteranopia", "Protanopia"]
+    );
+    assert_eq!(registry.next_after("Protanopia").name, "Classic");
+    assert_eq!(registry.next_after("missing").name, "Classic");
+}
+
+#[test]
+fn every_theme_keeps_board_colors_apart() {
+    for theme in &registry().0 {
+        let closest = min_distance(theme, Srgba::from);
+        assert!(closest.0 > 0.2, "{}: {closest:?}", theme.name);
+    }
+}
+
+#[test]
+fn high_contrast_theme_meets_aaa_contrast() {
+    let registry = registry();
+    let theme = registry.find("High Contrast").unwrap();
+    for (name, color) in board_colors(theme).into_iter().skip(1) {
+        let ratio = contrast(color, theme.arena.0);
+        assert!(ratio >= 7.0, "{name}: {ratio}");
+    }
+    assert!(contrast(theme.text.0, theme.button.0) >= 7.0);
+    assert!(contrast(theme.text.0, theme.background.0) >= 7.0);
+}
+
+// 危险区是另一种底色，不和地图上的东西比较，只要和竞技场分得开
+#[test]
+fn danger_zone_stands_out_from_arena() {
+    let registry = registry();
+    for theme in &registry.0 {
+        let d = distance(theme.danger.0.into(), theme.arena.0.into());
+        assert!(d > 0.2, "{}: {d}", theme.name);
+    }
+    for (name, matrix) in [("Deuteranopia", DEUTERANOPIA), ("Protanopia", PROTANOPIA)] {
+        let theme = registry.find(name).unwrap();
+        let see = |color| simulate(&matrix, color);
+        let d = distance(see(theme.danger.0), see(theme.arena.0));
+        assert!(d > 0.2, "{name}: {d}");
+    }
+}
+
+#[test]
+fn colour_blind_themes_stay_distinguishable() {
+    let registry = registry();
+    for (name, matrix) in [("Deuteranopia", DEUTERANOPIA), ("Protanopia", PROTANOPIA)] {
+        let theme = registry.find(name).unwrap();
+        let closest = min_distance(theme, |color| simulate(&matrix, color));
+        assert!(closest.0 > 0.25, "{name}: {closest:?}");
+    }
+}
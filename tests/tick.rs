@@ -0,0 +1,73 @@
+mod common;
+
+use std::time::Duration;
+
+use common::TestApp;
+use snake::actions::Position;
+use snake::tick::{GameTick, TickInterval};
+use snake::GameState;
+
+fn game_tick(game: &TestApp) -> u64 {
+    game.app.world().resource::<GameTick>().0
+}
+
+fn interval(game: &TestApp) -> Duration {
+    game.app.world().resource::<TickInterval>().0
+}
+
+#[test]
+fn long_frame_simulates_every_missed_tick() {
+    let mut game = TestApp::playing();
+    let step = interval(&game);
+    game.frame(step * 3);
+    assert_eq!(game_tick(&game), 3);
+    assert_eq!(
+        game.segments(),
+        [Position { x: 3, y: 6 }, Position { x: 3, y: 5 }]
+    );
+}
+
+#[test]
+fn short_frames_wait_for_the_next_tick() {
+    let mut game = TestApp::playing();
+    let step = interval(&game);
+    game.frame(step / 2);
+    assert_eq!(game_tick(&game), 0);
+    assert_eq!(game.head(), Position { x: 3, y: 3 });
+    game.frame(step / 2);
+    game.frame(step / 2);
+    assert_eq!(game_tick(&game), 1);
+    assert_eq!(game.head(), Position { x: 3, y: 4 });
+}
+
+#[test]
+fn frame_rate_does_not_change_the_game() {
+    let mut steady = TestApp::seeded(7);
+    let mut choppy = TestApp::seeded(7);
+    steady.set_state(GameState::Playing);
+    choppy.set_state(GameState::Playing);
+    let step = interval(&steady);
+    // 一个每帧正好一个节拍，一个帧长忽长忽短
+    let frames = [step / 3, step * 2, step / 7, step * 3 / 2];
+    let mut i = 0;
+    while game_tick(&choppy) < 15 {
+        choppy.frame(frames[i % frames.len()]);
+        i += 1;
+    }
+    let ticks = game_tick(&choppy);
+    steady.advance_ticks(ticks as u32);
+    assert_eq!(game_tick(&steady), ticks);
+    assert_eq!(steady.segments(), choppy.segments());
+    assert_eq!(steady.food(), choppy.food());
+    assert_eq!(steady.score(), choppy.score());
+}
+
+#[test]
+fn tick_counter_restarts_with_each_game() {
+    let mut game = TestApp::playing();
+    game.advance_ticks(4);
+    assert_eq!(game_tick(&game), 4);
+    game.set_state(GameState::Menu);
+    game.set_state(GameState::Playing);
+    assert_eq!(game_tick(&game), 0);
+}
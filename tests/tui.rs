@@ -0,0 +1,64 @@
+#![cfg(feature = "tui")]
+
+use bevy::prelude::*;
+use crossterm::event::KeyCode as TermKey;
+use snake::actions::control::{GameControl, KeyBindings};
+use snake::i18n::{Language, Localizer};
+use snake::settings::Settings;
+use snake::tui::{map_key, TuiPlugin};
+use snake::HeadlessPlugin;
+
+#[test]
+fn letters_and_digits_map_to_their_keys() {
+    assert_eq!(map_key(TermKey::Char('j')), Some(KeyCode::KeyJ));
+    assert_eq!(map_key(TermKey::Char('J')), Some(KeyCode::KeyJ));
+    assert_eq!(map_key(TermKey::Char('7')), Some(KeyCode::Digit7));
+    assert_eq!(map_key(TermKey::Up), Some(KeyCode::ArrowUp));
+    assert_eq!(map_key(TermKey::Char('?')), None);
+}
+
+#[test]
+fn rebound_letter_keys_can_be_typed_in_the_terminal() {
+    let mut bindings = KeyBindings::default();
+    bindings.bind(GameControl::Up, KeyCode::KeyI);
+    bindings.bind(GameControl::Left, KeyCode::KeyJ);
+    bindings.bind(GameControl::Down, KeyCode::KeyK);
+    bindings.bind(GameControl::Right, KeyCode::KeyL);
+    for (c, control) in "ijkl".chars().zip([
+        GameControl::Up,
+        GameControl::Left,
+        GameControl::Down,
+        GameControl::Right,
+    ]) {
+        let key = map_key(TermKey::Char(c)).unwrap();
+        assert_eq!(bindings.keys(control), &[key]);
+    }
+}
+
+#[test]
+fn follows_the_saved_language() {
+    let mut app = App::new();
+    app.insert_resource(Settings {
+        language: Language::Chinese,
+        ..default()
+    })
+    .add_plugins((HeadlessPlugin, TuiPlugin));
+    let localizer = app.world().resource::<Localizer>();
+    assert_eq!(localizer.language(), Language::Chinese);
+    assert_eq!(localizer.get("game-over"), "游戏结束");
+}
+
+#[test]
+fn every_hint_has_a_translation() {
+    for language in Language::ALL {
+        let localizer = Localizer::new(language);
+        for key in [
+            "tui-mode",
+            "tui-hint-menu",
+            "tui-hint-playing",
+            "tui-hint-game-over",
+        ] {
+            assert!(localizer.has(key), "{language:?} {key}");
+        }
+    }
+}